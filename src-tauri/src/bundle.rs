@@ -0,0 +1,210 @@
+// Reads version information back out of installed app bundles
+
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::tools::{ArtifactKind, ToolDefinition};
+use crate::{get_apps_dir, tool_app_path};
+
+// Written next to the bundle at install time. The bundle itself is left
+// untouched so its code signature stays valid.
+pub fn version_marker_path(tool_id: &str) -> PathBuf {
+    marker_path_in(&get_apps_dir(), tool_id)
+}
+
+fn marker_path_in(apps_dir: &Path, tool_id: &str) -> PathBuf {
+    apps_dir.join(format!(".{}.version", tool_id))
+}
+
+pub fn write_version_marker(tool_id: &str, version: &str) {
+    let _ = fs::write(version_marker_path(tool_id), version);
+}
+
+pub fn remove_version_marker(tool_id: &str) {
+    let _ = fs::remove_file(version_marker_path(tool_id));
+}
+
+pub fn read_version_marker_in(apps_dir: &Path, tool_id: &str) -> Option<String> {
+    let version = fs::read_to_string(marker_path_in(apps_dir, tool_id)).ok()?;
+    let version = version.trim();
+    (!version.is_empty()).then(|| version.to_string())
+}
+
+// The version actually on disk, independent of what config.json recorded:
+// Info.plist for app bundles and a VERSION file for plain folders and
+// executables. Cheap enough to run on every status check.
+pub fn detect_installed_version(path: &Path) -> Option<String> {
+    if path.join("Contents").join("Info.plist").exists() {
+        read_bundle_version(path)
+    } else if path.is_dir() {
+        read_version_file(&path.join("VERSION"))
+    } else if path.is_file() {
+        read_version_file(&path.with_file_name("VERSION"))
+    } else {
+        None
+    }
+}
+
+// Also asks a bare executable for its `--version`, which starts the tool.
+// Only for install and adopt time; the result is cached in the version marker.
+pub fn probe_installed_version(path: &Path) -> Option<String> {
+    detect_installed_version(path).or_else(|| path.is_file().then(|| executable_version(path))?)
+}
+
+pub fn detect_tool_version(tool: &ToolDefinition) -> Option<String> {
+    let path = tool_app_path(tool.id)?;
+    detect_tool_version_at(tool, &path, &get_apps_dir())
+}
+
+// Scripts carry no version of their own, so only the marker says what's installed
+pub fn detect_tool_version_at(
+    tool: &ToolDefinition,
+    path: &Path,
+    apps_dir: &Path,
+) -> Option<String> {
+    match tool.kind {
+        // Executables without a VERSION file were probed once, when installed
+        ArtifactKind::App => detect_installed_version(path).or_else(|| {
+            path.is_file()
+                .then(|| read_version_marker_in(apps_dir, tool.id))?
+        }),
+        ArtifactKind::ResolveScript { .. } => path
+            .exists()
+            .then(|| read_version_marker_in(apps_dir, tool.id))
+            .flatten(),
+    }
+}
+
+fn read_version_file(path: &Path) -> Option<String> {
+    let content = fs::read_to_string(path).ok()?;
+    extract_version(&content)
+}
+
+// Some tools ignore --version and start normally, so don't wait forever
+fn executable_version(path: &Path) -> Option<String> {
+    let mut child = Command::new(path)
+        .arg("--version")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+
+    // Read while it runs, so a tool that writes a lot can't block on a full pipe
+    let mut stdout = child.stdout.take()?;
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut output = Vec::new();
+        let _ = stdout.read_to_end(&mut output);
+        let _ = sender.send(output);
+    });
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) if Instant::now() < deadline => thread::sleep(Duration::from_millis(50)),
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        }
+    }
+
+    // Something it started may hold the pipe open, so don't wait on that
+    let output = receiver.recv_timeout(Duration::from_secs(1)).ok()?;
+    extract_version(&String::from_utf8_lossy(&output))
+}
+
+// First dotted version-looking token, e.g. "resolve-sync v1.4.2 (abc123)" -> "1.4.2"
+fn extract_version(text: &str) -> Option<String> {
+    text.split_whitespace()
+        .map(|token| token.trim_matches(|c: char| !c.is_ascii_alphanumeric()))
+        .map(|token| token.trim_start_matches('v'))
+        .find(|token| {
+            token.starts_with(|c: char| c.is_ascii_digit())
+                && token.contains('.')
+                && token
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+'))
+        })
+        .map(str::to_string)
+}
+
+// "1.2" and "v1.2.0" describe the same release
+pub fn versions_match(a: &str, b: &str) -> bool {
+    let segments = |version: &str| -> Vec<String> {
+        let mut segments: Vec<String> = version
+            .trim()
+            .trim_start_matches('v')
+            .split('.')
+            .map(str::to_string)
+            .collect();
+        while segments.len() > 1 && segments.last().is_some_and(|s| s == "0") {
+            segments.pop();
+        }
+        segments
+    };
+    segments(a) == segments(b)
+}
+
+// CFBundleShortVersionString, falling back to CFBundleVersion
+pub fn read_bundle_version(app_path: &Path) -> Option<String> {
+    let plist_path = app_path.join("Contents").join("Info.plist");
+    let content = fs::read(&plist_path).ok()?;
+
+    ["CFBundleShortVersionString", "CFBundleVersion"]
+        .iter()
+        .find_map(|key| {
+            plist_string_value(&String::from_utf8_lossy(&content), key)
+                .or_else(|| plutil_extract(&plist_path, key))
+        })
+}
+
+// The binary to run directly, so the launcher can own the process instead of
+// handing it to `open`
+pub fn bundle_executable(app_path: &Path) -> Option<PathBuf> {
+    if app_path.is_file() {
+        return Some(app_path.to_path_buf());
+    }
+
+    let plist_path = app_path.join("Contents").join("Info.plist");
+    let content = fs::read(&plist_path).ok()?;
+    let name = plist_string_value(&String::from_utf8_lossy(&content), "CFBundleExecutable")
+        .or_else(|| plutil_extract(&plist_path, "CFBundleExecutable"))?;
+    let executable = app_path.join("Contents").join("MacOS").join(name);
+    executable.is_file().then_some(executable)
+}
+
+// Minimal lookup for XML plists: the <string> following <key>name</key>
+fn plist_string_value(plist: &str, key: &str) -> Option<String> {
+    let after_key = plist.split(&format!("<key>{}</key>", key)).nth(1)?;
+    let value = after_key
+        .trim_start()
+        .strip_prefix("<string>")?
+        .split("</string>")
+        .next()?
+        .trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+// Binary plists need plutil, which only exists on macOS
+fn plutil_extract(plist_path: &Path, key: &str) -> Option<String> {
+    let output = Command::new("plutil")
+        .args(["-extract", key, "raw", "-o", "-"])
+        .arg(plist_path)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!value.is_empty()).then_some(value)
+}
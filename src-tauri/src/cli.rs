@@ -0,0 +1,282 @@
+// Headless command-line mode, e.g. `story-launcher install resolve-sync --json`.
+// Shares the install engine with the GUI but never starts the webview.
+
+use serde::Serialize;
+
+use crate::diagnostics::{diagnose, format_report};
+use crate::discovery::adopt;
+use crate::installer::{install, list_versions, tool_status};
+use crate::tools::{find_tool, TOOLS};
+use crate::{get_installed_tools, launch, ActionResult, ToolStatus};
+
+const EXIT_OK: i32 = 0;
+const EXIT_FAILURE: i32 = 1;
+const EXIT_USAGE: i32 = 2;
+
+const USAGE: &str = "Usage: story-launcher <command> [options]
+
+Commands:
+  status [tool]                 Show installed and latest versions
+  install <tool> [--version V]  Install a tool (latest by default)
+  update <tool> | --all         Update one tool or every installed tool
+  launch <tool>                 Launch an installed tool
+  list-versions <tool>          List released versions of a tool
+  adopt <tool> [--move]         Manage an existing install, optionally moving it
+  doctor                        Check network, disk, permissions and config
+
+Options:
+  --json                        Print machine-readable output";
+
+#[derive(Serialize)]
+struct ToolStatusOutput {
+    tool_id: String,
+    #[serde(flatten)]
+    status: ToolStatus,
+}
+
+#[derive(Serialize)]
+struct ActionOutput {
+    tool_id: String,
+    #[serde(flatten)]
+    result: ActionResult,
+}
+
+// Returns the process exit code, or None when the arguments aren't a CLI
+// invocation and the GUI should start instead
+pub fn run(args: &[String]) -> Option<i32> {
+    let command = args.first()?;
+    if !matches!(
+        command.as_str(),
+        "status"
+            | "install"
+            | "update"
+            | "launch"
+            | "list-versions"
+            | "adopt"
+            | "doctor"
+            | "help"
+            | "--help"
+    ) {
+        return None;
+    }
+
+    let json = args.iter().any(|arg| arg == "--json");
+    let mut positional = Vec::new();
+    let mut version = None;
+    let mut all = false;
+    let mut move_app = false;
+
+    let mut rest = args[1..].iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--json" => {}
+            "--all" => all = true,
+            "--move" => move_app = true,
+            "--version" => match rest.next() {
+                Some(value) => version = Some(value.clone()),
+                None => return Some(usage_error("--version needs a value")),
+            },
+            flag if flag.starts_with("--") => {
+                return Some(usage_error(&format!("Unknown option: {}", flag)))
+            }
+            _ => positional.push(arg.clone()),
+        }
+    }
+
+    let code = match (command.as_str(), positional.as_slice()) {
+        ("status", []) => status(&installed_or_all(), json),
+        ("status", [tool_id]) => status(std::slice::from_ref(tool_id), json),
+        ("install", [tool_id]) => with_tool(tool_id, || {
+            let result = install(tool_id, version.as_deref())
+                .map(|installed| installed.summary())
+                .into();
+            print_actions(vec![(tool_id.clone(), result)], json)
+        }),
+        ("update", []) if all => update(&get_installed_tools(), json),
+        ("update", [tool_id]) if !all => {
+            with_tool(tool_id, || update(std::slice::from_ref(tool_id), json))
+        }
+        ("launch", [tool_id]) => with_tool(tool_id, || {
+            print_actions(vec![(tool_id.clone(), launch(tool_id, &[]).into())], json)
+        }),
+        ("list-versions", [tool_id]) => with_tool(tool_id, || versions(tool_id, json)),
+        ("adopt", [tool_id]) => with_tool(tool_id, || {
+            let result = adopt(tool_id, None, move_app).into();
+            print_actions(vec![(tool_id.clone(), result)], json)
+        }),
+        ("doctor", []) => doctor(json),
+        ("help", _) | ("--help", _) => {
+            println!("{}", USAGE);
+            EXIT_OK
+        }
+        _ => usage_error(&format!("Invalid arguments for '{}'", command)),
+    };
+
+    Some(code)
+}
+
+fn usage_error(message: &str) -> i32 {
+    eprintln!("{}\n\n{}", message, USAGE);
+    EXIT_USAGE
+}
+
+fn with_tool(tool_id: &str, f: impl FnOnce() -> i32) -> i32 {
+    if find_tool(tool_id).is_none() {
+        let known: Vec<&str> = TOOLS.iter().map(|tool| tool.id).collect();
+        eprintln!(
+            "Unknown tool: {} (known tools: {})",
+            tool_id,
+            known.join(", ")
+        );
+        return EXIT_USAGE;
+    }
+    f()
+}
+
+fn installed_or_all() -> Vec<String> {
+    let installed = get_installed_tools();
+    if installed.is_empty() {
+        TOOLS.iter().map(|tool| tool.id.to_string()).collect()
+    } else {
+        installed
+    }
+}
+
+fn status(tool_ids: &[String], json: bool) -> i32 {
+    let statuses: Vec<ToolStatusOutput> = tool_ids
+        .iter()
+        .map(|tool_id| ToolStatusOutput {
+            tool_id: tool_id.clone(),
+            status: tool_status(tool_id),
+        })
+        .collect();
+
+    if json {
+        print_json(&statuses);
+    } else {
+        for output in &statuses {
+            let status = &output.status;
+            let installed = status
+                .installed_version
+                .as_deref()
+                .unwrap_or("not installed");
+            let latest = status.latest_version.as_deref().unwrap_or("unknown");
+            let mut line = format!("{}: {} (latest {})", output.tool_id, installed, latest);
+            if status.has_update {
+                line.push_str(" — update available");
+            }
+            if status.running {
+                line.push_str(" — running");
+            }
+            if status.version_mismatch {
+                let recorded = status.recorded_version.as_deref().unwrap_or("nothing");
+                line.push_str(&format!(" — config records {}", recorded));
+            }
+            if let Some(error) = &status.error {
+                line.push_str(&format!(" — error: {}", error));
+            }
+            println!("{}", line);
+        }
+    }
+
+    if statuses.iter().any(|output| output.status.error.is_some()) {
+        EXIT_FAILURE
+    } else {
+        EXIT_OK
+    }
+}
+
+fn update(tool_ids: &[String], json: bool) -> i32 {
+    let results = tool_ids
+        .iter()
+        .filter_map(|tool_id| {
+            let status = tool_status(tool_id);
+            if let Some(error) = status.error {
+                return Some((tool_id.clone(), error.into()));
+            }
+            if !status.has_update {
+                return None;
+            }
+            Some((
+                tool_id.clone(),
+                install(tool_id, None)
+                    .map(|installed| installed.summary())
+                    .into(),
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    if results.is_empty() && !json {
+        println!("Everything is up to date");
+        return EXIT_OK;
+    }
+    print_actions(results, json)
+}
+
+fn versions(tool_id: &str, json: bool) -> i32 {
+    match list_versions(tool_id) {
+        Ok(versions) => {
+            if json {
+                print_json(&versions);
+            } else {
+                for version in versions {
+                    println!("{}", version);
+                }
+            }
+            EXIT_OK
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            EXIT_FAILURE
+        }
+    }
+}
+
+fn doctor(json: bool) -> i32 {
+    let report = diagnose();
+    if json {
+        print_json(&report);
+    } else {
+        println!("{}", format_report(&report));
+    }
+
+    if report.ok {
+        EXIT_OK
+    } else {
+        EXIT_FAILURE
+    }
+}
+
+fn print_actions(results: Vec<(String, ActionResult)>, json: bool) -> i32 {
+    let failed = results.iter().any(|(_, result)| !result.success);
+    let outputs: Vec<ActionOutput> = results
+        .into_iter()
+        .map(|(tool_id, result)| ActionOutput { tool_id, result })
+        .collect();
+
+    if json {
+        print_json(&outputs);
+    } else {
+        for output in &outputs {
+            let line = format!("{}: {}", output.tool_id, output.result.message);
+            if output.result.success {
+                println!("{}", line);
+            } else {
+                eprintln!("{}", line);
+            }
+        }
+    }
+
+    if failed {
+        EXIT_FAILURE
+    } else {
+        EXIT_OK
+    }
+}
+
+fn print_json<T: Serialize>(value: &T) {
+    match serde_json::to_string_pretty(value) {
+        Ok(json) => println!("{}", json),
+        Err(e) => eprintln!("Failed to serialize output: {}", e),
+    }
+}
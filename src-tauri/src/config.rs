@@ -0,0 +1,628 @@
+// config.json persistence. The GUI, the CLI and background updates can all touch
+// the file at once, so writes go through a temp file + rename and every
+// read-modify-write cycle holds an advisory lock on config.json.lock.
+//
+// In system-wide mode (see location.rs) the `tools` records are kept in the
+// install root's shared manifest instead of the per-user config.json.
+//
+// Files carry a `schema_version`; older files are upgraded in memory by
+// MIGRATIONS on read and written back in the current format on the next save.
+
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::error::LauncherError;
+use crate::location::install_location_for;
+use crate::logging;
+use crate::policy::UpdatePolicySettings;
+use crate::recovery;
+use crate::scheduler::UpdateCheckSettings;
+use crate::tools::{LaunchProfile, WebApp};
+use crate::webview::WindowGeometry;
+use crate::{get_config_path, now_secs};
+
+pub const CURRENT_SCHEMA_VERSION: u32 = 1;
+
+// MIGRATIONS[n] upgrades a schema n document to schema n + 1
+type Migration = fn(&mut Map<String, Value>) -> Result<(), String>;
+const MIGRATIONS: [Migration; CURRENT_SCHEMA_VERSION as usize] = [migrate_v0_tool_versions];
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ToolsConfig {
+    pub schema_version: u32,
+    #[serde(default)]
+    pub tools: HashMap<String, ToolRecord>, // tool_id -> install record
+    #[serde(default)]
+    pub web_apps: Vec<WebApp>, // user bookmarks and overrides of built-in web apps
+    #[serde(default)]
+    pub window_geometry: HashMap<String, WindowGeometry>, // web_app_id -> last window geometry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub install_root: Option<PathBuf>, // overrides ~/.story-tools for installed apps
+    #[serde(default)]
+    pub system_wide: bool, // share installs with every user of the install root
+    #[serde(default)]
+    pub update_checks: UpdateCheckSettings,
+    #[serde(default)]
+    pub update_policy: UpdatePolicySettings,
+    #[serde(default)]
+    pub tool_settings: HashMap<String, ToolSettings>, // tool_id -> per-user preferences
+}
+
+impl Default for ToolsConfig {
+    fn default() -> Self {
+        ToolsConfig {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            tools: HashMap::new(),
+            web_apps: Vec::new(),
+            window_geometry: HashMap::new(),
+            install_root: None,
+            system_wide: false,
+            update_checks: UpdateCheckSettings::default(),
+            update_policy: UpdatePolicySettings::default(),
+            tool_settings: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ToolRecord {
+    pub version: String,
+    #[serde(default)]
+    pub installed_at: Option<u64>, // unix seconds; unknown for migrated records
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub install_path: Option<PathBuf>, // set for external installs adopted in place
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<PathBuf>, // installed outside the apps folder, e.g. Resolve scripts
+}
+
+impl ToolRecord {
+    pub fn new(version: &str) -> Self {
+        ToolRecord {
+            version: version.to_string(),
+            installed_at: Some(now_secs()),
+            install_path: None,
+            files: Vec::new(),
+        }
+    }
+}
+
+// How the user wants a tool run, kept out of ToolRecord so shared installs
+// don't share preferences
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct ToolSettings {
+    #[serde(default)]
+    pub auto_restart: bool, // relaunch the tool if it crashes
+    #[serde(default)]
+    pub profiles: Vec<LaunchProfile>, // user launch profiles and overrides of built-in ones
+    #[serde(default)]
+    pub startup: StartupSettings,
+}
+
+// Starting the tool automatically when the launcher starts
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct StartupSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub delay_secs: u64, // wait before launching, after any tools it starts after
+    #[serde(default)]
+    pub after: Vec<String>, // tool_ids to start first
+    #[serde(default)]
+    pub profile: Option<String>, // launch profile to use instead of a plain launch
+}
+
+pub fn load_config() -> ToolsConfig {
+    let mut config = load_user_config();
+    if let Some(manifest_path) = install_location_for(&config).manifest_path() {
+        config.tools = load_manifest_at(&manifest_path);
+    }
+    config
+}
+
+// Shared records are never rebuilt: recovery only sees this user's view of the
+// install root, and the file belongs to every user. Diagnostics reports it.
+fn load_manifest_at(path: &Path) -> HashMap<String, ToolRecord> {
+    match read_config_at(path) {
+        Ok(manifest) => manifest.unwrap_or_default().tools,
+        Err(e) => {
+            logging::error("manifest_read", &e, json!({ "path": path }));
+            HashMap::new()
+        }
+    }
+}
+
+// This user's config.json on its own, without shared install records
+pub fn load_user_config() -> ToolsConfig {
+    load_config_at(&get_config_path())
+}
+
+// Ok(None) when there is no config file yet
+pub fn read_config() -> Result<Option<ToolsConfig>, LauncherError> {
+    read_config_at(&get_config_path())
+}
+
+// Apply `f` to the current config and persist the result, holding the config
+// lock so concurrent writers can't drop each other's changes
+pub fn update_config<T>(f: impl FnOnce(&mut ToolsConfig) -> T) -> Result<T, LauncherError> {
+    let config_path = get_config_path();
+    if let Some(dir) = config_path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| LauncherError::from_io("Failed to create tools folder", e))?;
+    }
+
+    let Some(manifest_path) = install_location_for(&load_user_config()).manifest_path() else {
+        return update_config_at(&config_path, f);
+    };
+
+    // Locks are always taken manifest first, then config.json, so two writers
+    // can't deadlock. `f` sees the shared records in `tools`; the user's own
+    // (pre system-wide) records are put back before config.json is written.
+    // The manifest is only written when `f` changed the shared records, and
+    // before config.json so a failed shared write leaves both untouched.
+    // Creating the install root can fail for a user who can't write it; that
+    // only matters if there turns out to be something to write there
+    if let Some(dir) = manifest_path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    let manifest_lock = lock_config(&manifest_path);
+    let _lock = lock_config(&config_path)?;
+    let mut manifest = read_config_at(&manifest_path)?.unwrap_or_default();
+    let mut config = load_locked_config_at(&config_path)?;
+    check_schema_writable(&config_path, &config)?;
+
+    let shared_before = manifest.tools.clone();
+    let own_tools = mem::replace(&mut config.tools, mem::take(&mut manifest.tools));
+    let result = f(&mut config);
+    manifest.tools = mem::replace(&mut config.tools, own_tools);
+
+    if manifest.tools != shared_before {
+        manifest_lock?;
+        check_schema_writable(&manifest_path, &manifest)?;
+        write_config_at(&manifest_path, &manifest)
+            .map_err(|e| LauncherError::from_io("Failed to save shared install records", e))?;
+    }
+    write_config_at(&config_path, &config)
+        .map_err(|e| LauncherError::from_io("Failed to save config", e))?;
+
+    Ok(result)
+}
+
+pub(crate) fn load_config_at(path: &Path) -> ToolsConfig {
+    let result = match read_config_at(path) {
+        // Recovery rewrites the file, so like any other write it happens
+        // under the lock, after checking nobody fixed the file meanwhile
+        Err(LauncherError::Config(_)) => {
+            lock_config(path).and_then(|_lock| load_locked_config_at(path))
+        }
+        result => result.map(Option::unwrap_or_default),
+    };
+    result.unwrap_or_else(|e| {
+        logging::error("config_read", &e, json!({ "path": path }));
+        ToolsConfig::default()
+    })
+}
+
+// For callers holding the config lock. Errors other than an unparseable file
+// are returned, since writing a default config over the file would lose it.
+fn load_locked_config_at(path: &Path) -> Result<ToolsConfig, LauncherError> {
+    match read_config_at(path) {
+        Ok(config) => Ok(config.unwrap_or_default()),
+        Err(e @ LauncherError::Config(_)) => Ok(recovery::recover_config(path, e)),
+        Err(e) => Err(e),
+    }
+}
+
+pub(crate) fn read_config_at(path: &Path) -> Result<Option<ToolsConfig>, LauncherError> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read(path).map_err(|e| LauncherError::from_io("Failed to read config", e))?;
+    let value = serde_json::from_slice(&content)
+        .map_err(|e| LauncherError::Config(format!("Failed to parse config: {}", e)))?;
+    let document = migrate(value)?;
+    let schema_version = document.as_object().map(schema_version_of).unwrap_or(0);
+    serde_json::from_value(document).map(Some).map_err(|e| {
+        match schema_version > CURRENT_SCHEMA_VERSION {
+            // Not corrupt, just a format we don't know; recovery would destroy it
+            true => newer_schema_error(path, schema_version),
+            false => LauncherError::Config(format!("Failed to parse config: {}", e)),
+        }
+    })
+}
+
+// Upgrade a raw config document to CURRENT_SCHEMA_VERSION. Documents from a
+// newer launcher are passed through untouched; see `update_config_at`.
+pub(crate) fn migrate(value: Value) -> Result<Value, LauncherError> {
+    let Value::Object(mut document) = value else {
+        return Err(LauncherError::Config(
+            "Failed to parse config: expected a JSON object".to_string(),
+        ));
+    };
+
+    // Files written before schema_version existed are schema 0
+    let from = schema_version_of(&document);
+    for version in from..CURRENT_SCHEMA_VERSION {
+        MIGRATIONS[version as usize](&mut document).map_err(|e| {
+            LauncherError::Config(format!(
+                "Failed to migrate config from schema {}: {}",
+                version, e
+            ))
+        })?;
+        document.insert("schema_version".to_string(), json!(version + 1));
+    }
+
+    Ok(Value::Object(document))
+}
+
+fn schema_version_of(document: &Map<String, Value>) -> u32 {
+    document
+        .get("schema_version")
+        .and_then(Value::as_u64)
+        .unwrap_or(0) as u32
+}
+
+// Schema 0 stored each tool as a bare version string
+fn migrate_v0_tool_versions(document: &mut Map<String, Value>) -> Result<(), String> {
+    let Some(tools) = document.get_mut("tools") else {
+        return Ok(());
+    };
+    let Value::Object(tools) = tools else {
+        return Err("\"tools\" is not an object".to_string());
+    };
+
+    for (tool_id, record) in tools.iter_mut() {
+        let Value::String(version) = record else {
+            return Err(format!("unexpected entry for tool {}", tool_id));
+        };
+        *record = json!({ "version": version, "installed_at": null });
+    }
+    Ok(())
+}
+
+// Write the config back in the current format if it was read from an older one
+pub fn upgrade_config() {
+    let path = get_config_path();
+    let Ok(content) = fs::read(&path) else {
+        return;
+    };
+    let Ok(Value::Object(document)) = serde_json::from_slice::<Value>(&content) else {
+        return;
+    };
+
+    let from = schema_version_of(&document);
+    if from >= CURRENT_SCHEMA_VERSION {
+        return;
+    }
+
+    let fields = json!({ "from": from, "to": CURRENT_SCHEMA_VERSION });
+    match update_config_at(&path, |_| ()) {
+        Ok(()) => logging::info("config_migrated", fields),
+        Err(e) => logging::error("config_migrated", &e, fields),
+    }
+}
+
+pub(crate) fn update_config_at<T>(
+    path: &Path,
+    f: impl FnOnce(&mut ToolsConfig) -> T,
+) -> Result<T, LauncherError> {
+    let _lock = lock_config(path)?;
+
+    let mut config = load_locked_config_at(path)?;
+    check_schema_writable(path, &config)?;
+    let result = f(&mut config);
+    write_config_at(path, &config)
+        .map_err(|e| LauncherError::from_io("Failed to save config", e))?;
+
+    Ok(result)
+}
+
+// Rewriting a config in our older format would drop whatever a newer version added
+fn check_schema_writable(path: &Path, config: &ToolsConfig) -> Result<(), LauncherError> {
+    if config.schema_version > CURRENT_SCHEMA_VERSION {
+        return Err(newer_schema_error(path, config.schema_version));
+    }
+    Ok(())
+}
+
+fn newer_schema_error(path: &Path, schema_version: u32) -> LauncherError {
+    LauncherError::NewerConfig(format!(
+        "{} was written by a newer Story Launcher (schema {}); update the launcher to make changes",
+        path.file_name().unwrap_or_default().to_string_lossy(),
+        schema_version
+    ))
+}
+
+// Exclusive advisory lock, released when the returned file is dropped. The lock
+// lives on a sibling file because config.json itself is replaced on every write.
+fn lock_config(path: &Path) -> Result<File, LauncherError> {
+    let lock_path = lock_path(path);
+    // Another user may own a shared manifest's lock file; locking works read-only too
+    let lock_file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .or_else(|_| File::open(&lock_path))
+        .map_err(|e| LauncherError::from_io("Failed to open config lock", e))?;
+    lock_file
+        .lock()
+        .map_err(|e| LauncherError::from_io("Failed to lock config", e))?;
+    Ok(lock_file)
+}
+
+fn lock_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".lock");
+    path.with_file_name(name)
+}
+
+// Write to a temp file in the same directory, fsync it, then rename over the
+// original so readers only ever see the old or the new file in full
+pub(crate) fn write_config_at(path: &Path, config: &ToolsConfig) -> io::Result<()> {
+    static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let content = serde_json::to_string_pretty(config)?;
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(format!(
+        ".{}-{}.tmp",
+        std::process::id(),
+        TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let temp_path = path.with_file_name(temp_name);
+
+    let result = (|| {
+        let mut file = File::create(&temp_path)?;
+        // Keep the original's permissions so a group-writable shared manifest stays that way
+        if let Ok(metadata) = fs::metadata(path) {
+            file.set_permissions(metadata.permissions())?;
+        }
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&temp_path, path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result?;
+
+    // Persist the rename itself; directories can't be opened this way on Windows
+    #[cfg(unix)]
+    if let Some(dir) = path.parent() {
+        File::open(dir)?.sync_all()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use std::sync::{Arc, Barrier};
+    use std::thread;
+
+    #[test]
+    fn concurrent_updates_are_not_lost() {
+        let dir = TempDir::new("config-concurrent");
+        let path = dir.join("config.json");
+        let writers = 16;
+        let barrier = Arc::new(Barrier::new(writers));
+
+        let handles: Vec<_> = (0..writers)
+            .map(|i| {
+                let path = path.clone();
+                let barrier = barrier.clone();
+                thread::spawn(move || {
+                    barrier.wait();
+                    for round in 0..10 {
+                        update_config_at(&path, |config| {
+                            config.tools.insert(
+                                format!("tool-{}", i),
+                                ToolRecord::new(&format!("1.0.{}", round)),
+                            );
+                        })
+                        .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let config = read_config_at(&path).unwrap().unwrap();
+        assert_eq!(config.tools.len(), writers);
+        for i in 0..writers {
+            assert_eq!(config.tools[&format!("tool-{}", i)].version, "1.0.9");
+        }
+    }
+
+    #[test]
+    fn readers_never_see_a_partial_file() {
+        let dir = TempDir::new("config-readers");
+        let path = dir.join("config.json");
+        write_config_at(&path, &ToolsConfig::default()).unwrap();
+
+        let writer_path = path.clone();
+        let writer = thread::spawn(move || {
+            for i in 0..200 {
+                update_config_at(&writer_path, |config| {
+                    // Large enough that a torn write would be easy to catch
+                    for j in 0..50 {
+                        config.tools.insert(
+                            format!("tool-{}", j),
+                            ToolRecord::new(&format!("{}.0.0", i)),
+                        );
+                    }
+                })
+                .unwrap();
+            }
+        });
+
+        while !writer.is_finished() {
+            assert!(read_config_at(&path).is_ok());
+        }
+        writer.join().unwrap();
+    }
+
+    #[test]
+    fn write_leaves_no_temp_files() {
+        let dir = TempDir::new("config-temp-files");
+        let path = dir.join("config.json");
+        update_config_at(&path, |config| {
+            config
+                .tools
+                .insert("resolve-sync".into(), ToolRecord::new("1.0.0"));
+        })
+        .unwrap();
+
+        let mut names: Vec<String> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["config.json", "config.json.lock"]);
+    }
+
+    fn read_fixture(name: &str, content: &str) -> ToolsConfig {
+        let dir = TempDir::new(&format!("config-{}", name));
+        let path = dir.join("config.json");
+        fs::write(&path, content).unwrap();
+        read_config_at(&path).unwrap().unwrap()
+    }
+
+    // The original format: only installed tool versions
+    #[test]
+    fn migrates_schema_0_tools_only() {
+        let config = read_fixture("schema-0", r#"{ "tools": { "resolve-sync": "1.2.0" } }"#);
+
+        assert_eq!(config.schema_version, CURRENT_SCHEMA_VERSION);
+        assert_eq!(
+            config.tools["resolve-sync"],
+            ToolRecord {
+                version: "1.2.0".to_string(),
+                installed_at: None,
+                install_path: None,
+                files: Vec::new(),
+            }
+        );
+        assert!(config.web_apps.is_empty());
+    }
+
+    // Schema 0 after web app bookmarks and window geometry were added
+    #[test]
+    fn migrates_schema_0_with_web_apps_and_geometry() {
+        let config = read_fixture(
+            "schema-0-web-apps",
+            r#"{
+                "tools": { "resolve-sync": "1.3.1" },
+                "web_apps": [
+                    { "id": "frame", "name": "Frame", "url": "https://frame.io", "open_in_window": true }
+                ],
+                "window_geometry": {
+                    "frame": { "x": 10.0, "y": 20.0, "width": 1200.0, "height": 800.0 }
+                }
+            }"#,
+        );
+
+        assert_eq!(config.schema_version, CURRENT_SCHEMA_VERSION);
+        assert_eq!(config.tools["resolve-sync"].version, "1.3.1");
+        assert_eq!(config.web_apps.len(), 1);
+        assert!(config.web_apps[0].open_in_window);
+        assert_eq!(config.window_geometry["frame"].width, 1200.0);
+    }
+
+    #[test]
+    fn migrates_empty_document() {
+        let config = read_fixture("empty", "{}");
+
+        assert_eq!(config.schema_version, CURRENT_SCHEMA_VERSION);
+        assert!(config.tools.is_empty());
+    }
+
+    #[test]
+    fn current_schema_round_trips() {
+        let dir = TempDir::new("config-current");
+        let path = dir.join("config.json");
+        update_config_at(&path, |config| {
+            config
+                .tools
+                .insert("resolve-sync".into(), ToolRecord::new("2.0.0"));
+        })
+        .unwrap();
+
+        let written: Value = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+        assert_eq!(written["schema_version"], json!(CURRENT_SCHEMA_VERSION));
+        assert_eq!(written["tools"]["resolve-sync"]["version"], "2.0.0");
+
+        let config = read_config_at(&path).unwrap().unwrap();
+        assert_eq!(config.tools["resolve-sync"].version, "2.0.0");
+    }
+
+    #[test]
+    fn upgraded_file_is_written_in_current_format() {
+        let dir = TempDir::new("config-upgrade");
+        let path = dir.join("config.json");
+        fs::write(&path, r#"{ "tools": { "resolve-sync": "1.0.0" } }"#).unwrap();
+
+        update_config_at(&path, |_| ()).unwrap();
+
+        let written: Value = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+        assert_eq!(written["schema_version"], json!(CURRENT_SCHEMA_VERSION));
+        assert_eq!(written["tools"]["resolve-sync"]["version"], "1.0.0");
+    }
+
+    #[test]
+    fn rejects_malformed_schema_0_tools() {
+        let result = migrate(json!({ "tools": { "resolve-sync": 3 } }));
+        assert!(matches!(result, Err(LauncherError::Config(_))));
+    }
+
+    #[test]
+    fn newer_schema_is_readable_but_not_overwritten() {
+        let dir = TempDir::new("config-newer");
+        let path = dir.join("config.json");
+        let newer = format!(
+            r#"{{ "schema_version": {}, "tools": {{ "resolve-sync": {{ "version": "9.0.0", "channel": "beta" }} }} }}"#,
+            CURRENT_SCHEMA_VERSION + 1
+        );
+        fs::write(&path, &newer).unwrap();
+
+        let config = read_config_at(&path).unwrap().unwrap();
+        assert_eq!(config.tools["resolve-sync"].version, "9.0.0");
+
+        assert!(update_config_at(&path, |_| ()).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), newer);
+    }
+
+    // A newer launcher that changed a field's type must not look corrupt
+    #[test]
+    fn unreadable_newer_schema_is_never_recovered() {
+        let dir = TempDir::new("config-newer-unreadable");
+        let path = dir.join("config.json");
+        let newer = format!(
+            r#"{{ "schema_version": {}, "tools": {{ "resolve-sync": ["9.0.0", "beta"] }} }}"#,
+            CURRENT_SCHEMA_VERSION + 1
+        );
+        fs::write(&path, &newer).unwrap();
+
+        assert!(matches!(
+            read_config_at(&path),
+            Err(LauncherError::NewerConfig(_))
+        ));
+        assert!(load_config_at(&path).tools.is_empty());
+        assert!(matches!(
+            update_config_at(&path, |_| ()),
+            Err(LauncherError::NewerConfig(_))
+        ));
+        assert_eq!(fs::read_to_string(&path).unwrap(), newer);
+    }
+}
@@ -0,0 +1,256 @@
+// Router for story-launcher:// URLs, e.g.
+//   story-launcher://install/resolve-sync?version=1.4.0
+//   story-launcher://launch/resolve-sync?project=My%20Project
+//   story-launcher://open/spellbook
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime, Url};
+
+use crate::error::LauncherError;
+use crate::installer::install;
+use crate::supervisor::{launch_supervised, LaunchOptions};
+use crate::tools::find_tool;
+use crate::tray::{refresh_tray, show_main_window};
+use crate::{get_web_apps, open_web_app_by_id, run_blocking, AppState, InstallResult};
+
+pub const SCHEME: &str = "story-launcher";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeepLinkAction {
+    Install {
+        tool_id: String,
+        version: Option<String>,
+    },
+    Launch {
+        tool_id: String,
+        project: Option<String>,
+    },
+    OpenWebApp {
+        web_app_id: String,
+    },
+    OpenLauncher,
+}
+
+// Installs triggered from a link wait here until the user confirms them
+#[derive(Debug, Serialize, Clone)]
+pub struct PendingInstall {
+    pub tool_id: String,
+    pub tool_name: String,
+    pub version: Option<String>,
+}
+
+pub fn parse_deep_link(url: &Url) -> Result<DeepLinkAction, String> {
+    if url.scheme() != SCHEME {
+        return Err(format!("Unsupported URL scheme: {}", url.scheme()));
+    }
+
+    let action = url.host_str().unwrap_or_default();
+    let target = url.path().trim_matches('/');
+    let query = |key: &str| {
+        url.query_pairs()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.into_owned())
+            .filter(|v| !v.is_empty())
+    };
+
+    match action {
+        "install" => {
+            let tool_id = validate_tool(target)?;
+            let version = query("version");
+            if let Some(version) = &version {
+                let valid = version
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+'));
+                if !valid {
+                    return Err(format!("Invalid version: {}", version));
+                }
+            }
+            Ok(DeepLinkAction::Install { tool_id, version })
+        }
+        "launch" => Ok(DeepLinkAction::Launch {
+            tool_id: validate_tool(target)?,
+            project: query("project"),
+        }),
+        "open" if target.is_empty() || target == "launcher" => Ok(DeepLinkAction::OpenLauncher),
+        "open" => {
+            if !get_web_apps().iter().any(|web_app| web_app.id == target) {
+                return Err(format!("Unknown web app: {}", target));
+            }
+            Ok(DeepLinkAction::OpenWebApp {
+                web_app_id: target.to_string(),
+            })
+        }
+        _ => Err(format!("Unknown deep link action: {}", action)),
+    }
+}
+
+fn validate_tool(tool_id: &str) -> Result<String, String> {
+    match find_tool(tool_id) {
+        Some(tool) => Ok(tool.id.to_string()),
+        None => Err(format!("Unknown tool: {}", tool_id)),
+    }
+}
+
+pub fn handle_deep_link<R: Runtime>(app: &AppHandle<R>, url: &Url) {
+    let action = match parse_deep_link(url) {
+        Ok(action) => action,
+        Err(e) => {
+            let _ = app.emit("deep-link-error", e);
+            return;
+        }
+    };
+
+    match action {
+        DeepLinkAction::Install { tool_id, version } => {
+            let Some(tool) = find_tool(&tool_id) else {
+                return;
+            };
+            let pending = PendingInstall {
+                tool_id,
+                tool_name: tool.name.to_string(),
+                version,
+            };
+            *app.state::<AppState>().pending_install.lock().unwrap() = Some(pending.clone());
+
+            // Installing is never silent: ask the window to confirm first
+            show_main_window(app);
+            let _ = app.emit("deep-link-install-request", pending);
+        }
+        DeepLinkAction::Launch { tool_id, project } => {
+            let args: Vec<String> = project
+                .map(|project| vec!["--project".to_string(), project])
+                .unwrap_or_default();
+            let _ = launch_supervised(app, &tool_id, &LaunchOptions::with_args(&args));
+        }
+        DeepLinkAction::OpenWebApp { web_app_id } => {
+            let _ = open_web_app_by_id(app, &web_app_id);
+        }
+        DeepLinkAction::OpenLauncher => show_main_window(app),
+    }
+}
+
+// Links that arrive at startup fire before the window is listening
+#[tauri::command]
+pub fn get_pending_deep_link_install<R: Runtime>(app: AppHandle<R>) -> Option<PendingInstall> {
+    app.state::<AppState>()
+        .pending_install
+        .lock()
+        .unwrap()
+        .clone()
+}
+
+// Async so the install doesn't block the main thread
+#[tauri::command]
+pub async fn confirm_deep_link_install<R: Runtime>(
+    app: AppHandle<R>,
+    accept: bool,
+) -> InstallResult {
+    run_blocking(move || confirm_install(&app, accept)).await
+}
+
+fn confirm_install<R: Runtime>(app: &AppHandle<R>, accept: bool) -> InstallResult {
+    let pending = app
+        .state::<AppState>()
+        .pending_install
+        .lock()
+        .unwrap()
+        .take();
+    let Some(pending) = pending else {
+        return LauncherError::InvalidInput("No install is waiting for confirmation".to_string())
+            .into();
+    };
+
+    if !accept {
+        return LauncherError::InvalidInput(format!("Cancelled install of {}", pending.tool_name))
+            .into();
+    }
+
+    let result = install(&pending.tool_id, pending.version.as_deref());
+    refresh_tray(app);
+    let _ = app.emit("tools-changed", ());
+    result.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(url: &str) -> Result<DeepLinkAction, String> {
+        parse_deep_link(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn install_with_version() {
+        assert_eq!(
+            parse("story-launcher://install/resolve-sync?version=1.4.0-beta%2B2"),
+            Ok(DeepLinkAction::Install {
+                tool_id: "resolve-sync".to_string(),
+                version: Some("1.4.0-beta+2".to_string()),
+            })
+        );
+        assert_eq!(
+            parse("story-launcher://install/resolve-sync?version="),
+            Ok(DeepLinkAction::Install {
+                tool_id: "resolve-sync".to_string(),
+                version: None,
+            })
+        );
+    }
+
+    #[test]
+    fn launch_with_project() {
+        assert_eq!(
+            parse("story-launcher://launch/resolve-sync?project=My%20Project"),
+            Ok(DeepLinkAction::Launch {
+                tool_id: "resolve-sync".to_string(),
+                project: Some("My Project".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn open_without_target_opens_launcher() {
+        assert_eq!(
+            parse("story-launcher://open"),
+            Ok(DeepLinkAction::OpenLauncher)
+        );
+        assert_eq!(
+            parse("story-launcher://open/"),
+            Ok(DeepLinkAction::OpenLauncher)
+        );
+        assert_eq!(
+            parse("story-launcher://open/launcher"),
+            Ok(DeepLinkAction::OpenLauncher)
+        );
+    }
+
+    #[test]
+    fn rejects_other_schemes() {
+        assert!(parse("https://install/resolve-sync").is_err());
+        assert!(parse("story-launcher-evil://install/resolve-sync").is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_action() {
+        assert_eq!(
+            parse("story-launcher://uninstall/resolve-sync"),
+            Err("Unknown deep link action: uninstall".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_tool() {
+        assert_eq!(
+            parse("story-launcher://install/not-a-tool"),
+            Err("Unknown tool: not-a-tool".to_string())
+        );
+        assert!(parse("story-launcher://launch/..%2F..%2Fetc").is_err());
+        assert!(parse("story-launcher://launch").is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_version() {
+        assert!(parse("story-launcher://install/resolve-sync?version=1.0;rm").is_err());
+        assert!(parse("story-launcher://install/resolve-sync?version=..%2F1.0").is_err());
+    }
+}
@@ -0,0 +1,327 @@
+// Tools that need other tools installed first. Before an install, the
+// declared dependencies are resolved into an ordered plan of what's missing
+// or too old, each pinned to the newest release that satisfies it.
+
+use std::collections::HashSet;
+
+use semver::{Version, VersionReq};
+use serde::Serialize;
+
+use crate::bundle::detect_tool_version;
+use crate::error::LauncherError;
+use crate::installer::list_versions;
+use crate::tools::{find_tool, ToolDefinition, TOOLS};
+use crate::{get_installed_tools, get_installed_version};
+
+#[derive(Debug, Serialize, Clone)]
+pub struct InstallStep {
+    pub tool_id: String,
+    pub version: String,
+    pub installed_version: Option<String>, // None when the dependency is missing
+    pub required_by: String,
+}
+
+// Releases aren't always full semver ("1.4", "v2"), so pad missing segments
+pub fn parse_version(version: &str) -> Option<Version> {
+    let version = version.trim().trim_start_matches('v');
+    if let Ok(parsed) = Version::parse(version) {
+        return Some(parsed);
+    }
+
+    let (core, rest) = match version.find(['-', '+']) {
+        Some(index) => version.split_at(index),
+        None => (version, ""),
+    };
+    let mut segments: Vec<&str> = core.split('.').collect();
+    while segments.len() < 3 {
+        segments.push("0");
+    }
+    Version::parse(&format!("{}{}", segments.join("."), rest)).ok()
+}
+
+fn parse_requirement(requirement: &str) -> Result<VersionReq, LauncherError> {
+    VersionReq::parse(requirement).map_err(|e| {
+        LauncherError::Dependency(format!(
+            "Invalid version requirement {:?}: {}",
+            requirement, e
+        ))
+    })
+}
+
+fn current_version(tool_id: &str) -> Option<String> {
+    find_tool(tool_id)
+        .and_then(detect_tool_version)
+        .or_else(|| get_installed_version(tool_id))
+}
+
+// Dependencies to install before `tool_id`, deepest first
+pub fn install_plan(tool_id: &str) -> Result<Vec<InstallStep>, LauncherError> {
+    Planner {
+        find_tool: &find_tool,
+        current_version: &current_version,
+        releases: &list_versions,
+    }
+    .plan(tool_id)
+}
+
+// Where the plan's facts come from, so it can be worked out against fixtures
+struct Planner<'a> {
+    find_tool: &'a dyn Fn(&str) -> Option<&'static ToolDefinition>,
+    current_version: &'a dyn Fn(&str) -> Option<String>,
+    releases: &'a dyn Fn(&str) -> Result<Vec<String>, LauncherError>, // newest first
+}
+
+impl Planner<'_> {
+    fn plan(&self, tool_id: &str) -> Result<Vec<InstallStep>, LauncherError> {
+        let mut plan = Vec::new();
+        let mut visiting = HashSet::new();
+        self.resolve(tool_id, &mut visiting, &mut plan)?;
+        Ok(plan)
+    }
+
+    fn resolve(
+        &self,
+        tool_id: &str,
+        visiting: &mut HashSet<String>,
+        plan: &mut Vec<InstallStep>,
+    ) -> Result<(), LauncherError> {
+        let tool = (self.find_tool)(tool_id)
+            .ok_or_else(|| LauncherError::UnknownTool(tool_id.to_string()))?;
+        if !visiting.insert(tool_id.to_string()) {
+            return Err(LauncherError::Dependency(format!(
+                "Dependency cycle through {}",
+                tool_id
+            )));
+        }
+
+        for dependency in tool.dependencies {
+            let requirement = parse_requirement(dependency.version)?;
+
+            // Already chosen for another tool; it has to suit this one too
+            if let Some(step) = plan.iter().find(|step| step.tool_id == dependency.tool_id) {
+                if !parse_version(&step.version).is_some_and(|v| requirement.matches(&v)) {
+                    return Err(LauncherError::Dependency(format!(
+                        "{} requires {} {}, but {} needs {}",
+                        tool.name,
+                        dependency.tool_id,
+                        dependency.version,
+                        step.required_by,
+                        step.version
+                    )));
+                }
+                continue;
+            }
+
+            let installed_version = (self.current_version)(dependency.tool_id);
+            let satisfied = installed_version
+                .as_deref()
+                .and_then(parse_version)
+                .is_some_and(|version| requirement.matches(&version));
+            if satisfied {
+                continue;
+            }
+
+            self.resolve(dependency.tool_id, visiting, plan)?;
+
+            let version = (self.releases)(dependency.tool_id)?
+                .into_iter()
+                .find(|version| parse_version(version).is_some_and(|v| requirement.matches(&v)))
+                .ok_or_else(|| {
+                    LauncherError::Dependency(format!(
+                        "No release of {} satisfies {} (required by {})",
+                        dependency.tool_id, dependency.version, tool.name
+                    ))
+                })?;
+
+            plan.push(InstallStep {
+                tool_id: dependency.tool_id.to_string(),
+                version,
+                installed_version,
+                required_by: tool_id.to_string(),
+            });
+        }
+
+        visiting.remove(tool_id);
+        Ok(())
+    }
+}
+
+// Installed tools that declare a dependency on `tool_id`
+pub fn dependents(tool_id: &str) -> Vec<String> {
+    let installed = get_installed_tools();
+    TOOLS
+        .iter()
+        .filter(|tool| installed.iter().any(|id| id == tool.id))
+        .filter(|tool| tool.dependencies.iter().any(|dep| dep.tool_id == tool_id))
+        .map(|tool| tool.id.to_string())
+        .collect()
+}
+
+// Refuse a version of `tool_id` that an installed dependent can't work with
+pub fn check_dependents(tool_id: &str, version: &str) -> Result<(), LauncherError> {
+    let Some(parsed) = parse_version(version) else {
+        return Ok(());
+    };
+
+    for dependent in dependents(tool_id) {
+        let Some(tool) = find_tool(&dependent) else {
+            continue;
+        };
+        for dependency in tool
+            .dependencies
+            .iter()
+            .filter(|dep| dep.tool_id == tool_id)
+        {
+            if !parse_requirement(dependency.version)?.matches(&parsed) {
+                return Err(LauncherError::Dependency(format!(
+                    "{} requires {} {}, not {}",
+                    tool.name, tool_id, dependency.version, version
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+pub struct InstallPlan {
+    pub steps: Vec<InstallStep>,
+    pub error: Option<LauncherError>, // the dependencies can't be satisfied
+}
+
+// What installing `tool_id` would install first, for confirming with the user
+#[tauri::command]
+pub fn get_install_plan(tool_id: String) -> InstallPlan {
+    match install_plan(&tool_id) {
+        Ok(steps) => InstallPlan { steps, error: None },
+        Err(e) => InstallPlan {
+            steps: Vec::new(),
+            error: Some(e),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::tool;
+    use crate::tools::ToolDependency;
+
+    const fn fixture(id: &'static str, dependencies: &'static [ToolDependency]) -> ToolDefinition {
+        ToolDefinition {
+            dependencies,
+            ..tool(id)
+        }
+    }
+
+    const fn dep(tool_id: &'static str, version: &'static str) -> ToolDependency {
+        ToolDependency { tool_id, version }
+    }
+
+    static FIXTURES: &[ToolDefinition] = &[
+        fixture("codec", &[]),
+        fixture("plugin", &[dep("codec", "^2")]),
+        fixture("app", &[dep("plugin", ">=1.2, <2")]),
+        fixture("diamond", &[dep("plugin", "^1"), dep("codec", ">=2.0")]),
+        fixture("conflict", &[dep("plugin", "^1"), dep("codec", "^1")]),
+        fixture("cycle-a", &[dep("cycle-b", "*")]),
+        fixture("cycle-b", &[dep("cycle-a", "*")]),
+        fixture("future", &[dep("codec", "^3")]),
+    ];
+
+    fn releases(tool_id: &str) -> Result<Vec<String>, LauncherError> {
+        let versions: &[&str] = match tool_id {
+            "codec" => &["2.1.0", "2.0.0", "1.5.0"],
+            "plugin" => &["2.0.0", "1.3.0", "1.2.0", "1.0.0"],
+            _ => &["1.0.0"],
+        };
+        Ok(versions.iter().map(|v| v.to_string()).collect())
+    }
+
+    // `installed` is (tool_id, version) for what's already on disk
+    fn plan(tool_id: &str, installed: &[(&str, &str)]) -> Result<Vec<InstallStep>, LauncherError> {
+        let current_version = |id: &str| {
+            installed
+                .iter()
+                .find(|(installed_id, _)| *installed_id == id)
+                .map(|(_, version)| version.to_string())
+        };
+        Planner {
+            find_tool: &|id| FIXTURES.iter().find(|tool| tool.id == id),
+            current_version: &current_version,
+            releases: &releases,
+        }
+        .plan(tool_id)
+    }
+
+    fn steps(plan: &[InstallStep]) -> Vec<(&str, &str)> {
+        plan.iter()
+            .map(|step| (step.tool_id.as_str(), step.version.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn parses_full_and_partial_versions() {
+        assert_eq!(parse_version("1.4.2"), Some(Version::new(1, 4, 2)));
+        assert_eq!(parse_version("v1.4"), Some(Version::new(1, 4, 0)));
+        assert_eq!(parse_version(" 2 "), Some(Version::new(2, 0, 0)));
+        assert_eq!(
+            parse_version("1.0-beta.1"),
+            Some(Version::parse("1.0.0-beta.1").unwrap())
+        );
+        assert_eq!(parse_version("latest"), None);
+    }
+
+    // Deepest dependency first, each at the newest release that fits
+    #[test]
+    fn plans_missing_dependencies_in_order() {
+        let plan = plan("app", &[]).unwrap();
+        assert_eq!(steps(&plan), [("codec", "2.1.0"), ("plugin", "1.3.0")]);
+        assert_eq!(plan[0].required_by, "plugin");
+        assert_eq!(plan[1].required_by, "app");
+    }
+
+    #[test]
+    fn skips_satisfied_dependencies() {
+        let plan = plan("app", &[("plugin", "1.2.5")]).unwrap();
+        assert!(plan.is_empty());
+    }
+
+    #[test]
+    fn upgrades_dependencies_that_are_too_old() {
+        let plan = plan("app", &[("plugin", "1.0.0"), ("codec", "2.0.0")]).unwrap();
+        assert_eq!(steps(&plan), [("plugin", "1.3.0")]);
+        assert_eq!(plan[0].installed_version.as_deref(), Some("1.0.0"));
+    }
+
+    #[test]
+    fn shared_dependency_is_planned_once() {
+        let plan = plan("diamond", &[]).unwrap();
+        assert_eq!(steps(&plan), [("codec", "2.1.0"), ("plugin", "1.3.0")]);
+    }
+
+    // plugin pulls in codec 2.x, which the tool's own ^1 requirement rejects
+    #[test]
+    fn conflicting_requirements_are_rejected() {
+        assert!(matches!(
+            plan("conflict", &[]),
+            Err(LauncherError::Dependency(_))
+        ));
+    }
+
+    #[test]
+    fn cycles_are_rejected() {
+        assert!(matches!(
+            plan("cycle-a", &[]),
+            Err(LauncherError::Dependency(_))
+        ));
+    }
+
+    #[test]
+    fn unsatisfiable_requirement_is_rejected() {
+        assert!(matches!(
+            plan("future", &[]),
+            Err(LauncherError::Dependency(_))
+        ));
+    }
+}
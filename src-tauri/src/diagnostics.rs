@@ -0,0 +1,378 @@
+// Health checks behind the `run_diagnostics` command and `story-launcher doctor`
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use serde_json::json;
+
+use crate::bundle::{detect_tool_version_at, versions_match};
+use crate::config::{read_config_at, ToolsConfig};
+use crate::error::LauncherError;
+use crate::location::{install_location_for, InstallLocation};
+use crate::logging;
+use crate::recovery::get_config_recovery;
+use crate::resolve;
+use crate::tools::TOOLS;
+use crate::{get_config_path, get_tools_dir, read_config, run_blocking};
+
+const LOW_DISK_SPACE_MB: u64 = 1024;
+const CRITICAL_DISK_SPACE_MB: u64 = 200;
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DiagnosticCheck {
+    pub id: String,
+    pub label: String,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DiagnosticsReport {
+    pub ok: bool, // false when any check failed; warnings don't count
+    pub checks: Vec<DiagnosticCheck>,
+}
+
+// Diagnostics that couldn't run at all
+impl From<LauncherError> for DiagnosticsReport {
+    fn from(error: LauncherError) -> Self {
+        DiagnosticsReport {
+            ok: false,
+            checks: vec![check(
+                "diagnostics",
+                "Diagnostics",
+                CheckStatus::Fail,
+                error.to_string(),
+            )],
+        }
+    }
+}
+
+fn check(id: &str, label: &str, status: CheckStatus, detail: impl Into<String>) -> DiagnosticCheck {
+    DiagnosticCheck {
+        id: id.to_string(),
+        label: label.to_string(),
+        status,
+        detail: detail.into(),
+    }
+}
+
+// Async so the release source requests don't block the main thread
+#[tauri::command]
+pub async fn run_diagnostics() -> DiagnosticsReport {
+    run_blocking(diagnose).await
+}
+
+pub fn diagnose() -> DiagnosticsReport {
+    let mut checks = Vec::new();
+    // Read without load_config's recovery, so a damaged config is reported
+    // as it is instead of being rebuilt before it's checked
+    let config = read_config();
+    let location = install_location_for(
+        config
+            .as_ref()
+            .ok()
+            .and_then(Option::as_ref)
+            .unwrap_or(&ToolsConfig::default()),
+    );
+
+    checks.extend(check_release_sources());
+    checks.push(check_disk_space(&location.root));
+    checks.push(check_writable(
+        "tools_dir_writable",
+        "Tools folder",
+        &get_tools_dir(),
+    ));
+    checks.push(check_writable(
+        "apps_dir_writable",
+        "Apps folder",
+        &location.apps_dir(),
+    ));
+    checks.push(check_config(&config));
+    if let Ok(config) = &config {
+        checks.extend(check_installed_tools(config.as_ref(), &location));
+    }
+    checks.push(check_orphaned_apps(&location.apps_dir()));
+
+    let ok = checks.iter().all(|c| c.status != CheckStatus::Fail);
+    let report = DiagnosticsReport { ok, checks };
+    logging::info("diagnostics", json!({ "report": report }));
+    report
+}
+
+fn check_release_sources() -> Vec<DiagnosticCheck> {
+    let client = match reqwest::blocking::Client::builder()
+        .user_agent("Story-Launcher/1.0")
+        .timeout(Duration::from_secs(10))
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            return vec![check(
+                "network",
+                "Network",
+                CheckStatus::Fail,
+                format!("Failed to create HTTP client: {}", e),
+            )]
+        }
+    };
+
+    TOOLS
+        .iter()
+        .map(|tool| {
+            let id = format!("source:{}", tool.id);
+            let label = format!("{} release source", tool.name);
+            let url = format!("https://api.github.com/repos/{}", tool.repo);
+            let started = Instant::now();
+
+            match client.get(&url).send() {
+                Ok(response) if response.status().is_success() => check(
+                    &id,
+                    &label,
+                    CheckStatus::Pass,
+                    format!(
+                        "{} reachable in {} ms",
+                        tool.repo,
+                        started.elapsed().as_millis()
+                    ),
+                ),
+                Ok(response) if response.status() == 403 || response.status() == 429 => check(
+                    &id,
+                    &label,
+                    CheckStatus::Warn,
+                    format!("{} reachable but rate limited", tool.repo),
+                ),
+                Ok(response) => check(
+                    &id,
+                    &label,
+                    CheckStatus::Fail,
+                    format!("{} returned {}", tool.repo, response.status()),
+                ),
+                Err(e) => check(
+                    &id,
+                    &label,
+                    CheckStatus::Fail,
+                    format!("{} unreachable: {}", tool.repo, e),
+                ),
+            }
+        })
+        .collect()
+}
+
+fn check_disk_space(dir: &Path) -> DiagnosticCheck {
+    let label = "Disk space";
+    match available_space_mb(dir) {
+        Some(mb) if mb < CRITICAL_DISK_SPACE_MB => check(
+            "disk_space",
+            label,
+            CheckStatus::Fail,
+            format!("Only {} MB free", mb),
+        ),
+        Some(mb) if mb < LOW_DISK_SPACE_MB => check(
+            "disk_space",
+            label,
+            CheckStatus::Warn,
+            format!("{} MB free", mb),
+        ),
+        Some(mb) => check(
+            "disk_space",
+            label,
+            CheckStatus::Pass,
+            format!("{} MB free", mb),
+        ),
+        None => check(
+            "disk_space",
+            label,
+            CheckStatus::Warn,
+            "Could not determine free disk space",
+        ),
+    }
+}
+
+// Free space on the volume holding `dir`, via POSIX `df -Pk`
+fn available_space_mb(dir: &Path) -> Option<u64> {
+    // The tools folder may not exist yet; measure the closest existing ancestor
+    let existing = dir.ancestors().find(|p| p.exists())?;
+    let output = Command::new("df").arg("-Pk").arg(existing).output().ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    // Filesystem 1024-blocks Used Available Capacity Mounted-on
+    let available_kb: u64 = stdout
+        .lines()
+        .nth(1)?
+        .split_whitespace()
+        .nth(3)?
+        .parse()
+        .ok()?;
+    Some(available_kb / 1024)
+}
+
+// A folder that doesn't exist yet is checked through the closest one that
+// does, since that's where it would be created
+fn check_writable(id: &str, label: &str, dir: &Path) -> DiagnosticCheck {
+    let existing = dir.ancestors().find(|p| p.exists()).unwrap_or(dir);
+    let probe = existing.join(".story-launcher-write-test");
+    let result = fs::write(&probe, b"ok").and_then(|_| fs::remove_file(&probe));
+
+    match result {
+        Ok(()) if existing == dir => check(id, label, CheckStatus::Pass, dir.display().to_string()),
+        Ok(()) => check(
+            id,
+            label,
+            CheckStatus::Pass,
+            format!("{} (not created yet)", dir.display()),
+        ),
+        Err(e) => check(
+            id,
+            label,
+            CheckStatus::Fail,
+            format!("{} is not writable: {}", dir.display(), e),
+        ),
+    }
+}
+
+fn check_config(config: &Result<Option<ToolsConfig>, LauncherError>) -> DiagnosticCheck {
+    let label = "Config file";
+    match config {
+        Ok(Some(_)) if get_config_recovery().is_some() => check(
+            "config",
+            label,
+            CheckStatus::Warn,
+            "Rebuilt this session after the previous config could not be parsed",
+        ),
+        Ok(Some(config)) => check(
+            "config",
+            label,
+            CheckStatus::Pass,
+            format!("{} tool(s) recorded", config.tools.len()),
+        ),
+        Ok(None) => check(
+            "config",
+            label,
+            CheckStatus::Pass,
+            format!("{} not created yet", get_config_path().display()),
+        ),
+        Err(e) => check("config", label, CheckStatus::Fail, e.to_string()),
+    }
+}
+
+// Compare what config.json, or the shared manifest in system-wide mode,
+// records against what's actually on disk
+fn check_installed_tools(
+    config: Option<&ToolsConfig>,
+    location: &InstallLocation,
+) -> Vec<DiagnosticCheck> {
+    let records = match location.manifest_path() {
+        Some(manifest_path) => match read_config_at(&manifest_path) {
+            Ok(manifest) => manifest.unwrap_or_default().tools,
+            Err(e) => {
+                return vec![check(
+                    "manifest",
+                    "Shared install records",
+                    CheckStatus::Fail,
+                    e.to_string(),
+                )]
+            }
+        },
+        None => config
+            .map(|config| config.tools.clone())
+            .unwrap_or_default(),
+    };
+
+    TOOLS
+        .iter()
+        .filter_map(|tool| {
+            let id = format!("tool:{}", tool.id);
+            let record = records.get(tool.id);
+            let recorded = record.map(|record| &record.version);
+            // Same lookup as tool_app_path, without loading the config again
+            let path = record
+                .and_then(|record| record.install_path.clone())
+                .or_else(|| resolve::script_path(tool))
+                .unwrap_or_else(|| location.apps_dir().join(tool.app_name));
+            let on_disk = path.exists();
+
+            let (status, detail) = match (recorded, on_disk) {
+                (Some(version), true) => {
+                    match detect_tool_version_at(tool, &path, &location.apps_dir()) {
+                        Some(found) if !versions_match(&found, version) => (
+                            CheckStatus::Warn,
+                            format!(
+                                "Config records version {} but {} is installed",
+                                version, found
+                            ),
+                        ),
+                        _ => (CheckStatus::Pass, format!("Version {} installed", version)),
+                    }
+                }
+                (Some(version), false) => (
+                    CheckStatus::Fail,
+                    format!(
+                        "Config records version {} but {} is missing",
+                        version, tool.app_name
+                    ),
+                ),
+                (None, true) => (
+                    CheckStatus::Warn,
+                    format!("{} is on disk but not recorded in config", tool.app_name),
+                ),
+                (None, false) => return None,
+            };
+            Some(check(&id, tool.name, status, detail))
+        })
+        .collect()
+}
+
+fn check_orphaned_apps(apps_dir: &Path) -> DiagnosticCheck {
+    let label = "Orphaned apps";
+    let known: HashSet<&str> = TOOLS.iter().map(|tool| tool.app_name).collect();
+
+    let orphaned: Vec<String> = fs::read_dir(apps_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .filter(|name| !name.starts_with('.') && !known.contains(name.as_str()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if orphaned.is_empty() {
+        check("orphaned_apps", label, CheckStatus::Pass, "None found")
+    } else {
+        check(
+            "orphaned_apps",
+            label,
+            CheckStatus::Warn,
+            format!("Not managed by any tool: {}", orphaned.join(", ")),
+        )
+    }
+}
+
+// Human-readable report for the CLI
+pub fn format_report(report: &DiagnosticsReport) -> String {
+    report
+        .checks
+        .iter()
+        .map(|c| {
+            let marker = match c.status {
+                CheckStatus::Pass => "ok  ",
+                CheckStatus::Warn => "warn",
+                CheckStatus::Fail => "FAIL",
+            };
+            format!("[{}] {}: {}", marker, c.label, c.detail)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
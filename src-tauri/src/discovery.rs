@@ -0,0 +1,208 @@
+// Finds copies of a tool installed outside the launcher (e.g. dragged into
+// /Applications by hand) and brings them under management
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::Serialize;
+use serde_json::json;
+use tauri::{AppHandle, Runtime};
+
+use crate::bundle::{probe_installed_version, write_version_marker};
+use crate::config::ToolRecord;
+use crate::error::LauncherError;
+use crate::installer::SetAside;
+use crate::logging;
+use crate::process::{is_path_running, is_tool_running};
+use crate::tools::{find_tool, ArtifactKind, ToolDefinition};
+use crate::tray::refresh_tray;
+use crate::{
+    ensure_dirs, get_app_path, get_apps_dir, load_config, tool_app_path, update_config,
+    ActionResult,
+};
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallSource {
+    Managed,  // installed by the launcher into the apps folder
+    External, // adopted in place at a path the launcher doesn't own
+}
+
+// Known locations use `~/` for the user's home folder
+fn expand_home(location: &str) -> PathBuf {
+    match location.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir().unwrap_or_default().join(rest),
+        None => PathBuf::from(location),
+    }
+}
+
+// Existing installations in the tool's known locations, excluding the one
+// the launcher is already tracking
+pub fn discover_installations(tool: &ToolDefinition) -> Vec<PathBuf> {
+    let tracked = tool_app_path(tool.id);
+    tool.known_locations
+        .iter()
+        .map(|location| expand_home(location))
+        .filter(|path| path.exists() && Some(path) != tracked.as_ref())
+        .collect()
+}
+
+pub fn install_source(tool_id: &str) -> Option<InstallSource> {
+    let config = load_config();
+    let record = config.tools.get(tool_id)?;
+    Some(match record.install_path {
+        Some(_) => InstallSource::External,
+        None => InstallSource::Managed,
+    })
+}
+
+// Register an existing installation, either leaving it where it is or moving
+// it into the apps folder
+#[tauri::command]
+pub fn adopt_tool<R: Runtime>(
+    app: AppHandle<R>,
+    tool_id: String,
+    path: Option<String>,
+    migrate: bool,
+) -> ActionResult {
+    let result = adopt(&tool_id, path.map(PathBuf::from), migrate);
+    refresh_tray(&app);
+    result.into()
+}
+
+pub fn adopt(tool_id: &str, path: Option<PathBuf>, migrate: bool) -> Result<String, LauncherError> {
+    let result = adopt_installation(tool_id, path, migrate);
+    let fields = json!({ "tool_id": tool_id, "migrate": migrate });
+    match &result {
+        Ok(_) => logging::info("adopt", fields),
+        Err(e) => logging::error("adopt", e, fields),
+    }
+    result
+}
+
+fn adopt_installation(
+    tool_id: &str,
+    path: Option<PathBuf>,
+    migrate: bool,
+) -> Result<String, LauncherError> {
+    let tool = find_tool(tool_id).ok_or_else(|| LauncherError::UnknownTool(tool_id.to_string()))?;
+    // Scripts have no version to read back, so they can only be installed from releases
+    if tool.kind != ArtifactKind::App {
+        return Err(LauncherError::InvalidInput(format!(
+            "{} can't be adopted; install it from the launcher instead",
+            tool.name
+        )));
+    }
+
+    let source = match path {
+        Some(path) => path,
+        // Moving an adopted install into the launcher moves the copy in use
+        None if migrate && install_source(tool_id) == Some(InstallSource::External) => {
+            tool_app_path(tool_id).ok_or_else(|| LauncherError::UnknownTool(tool_id.to_string()))?
+        }
+        None => discover_installations(tool)
+            .into_iter()
+            .next()
+            .ok_or_else(|| LauncherError::NotInstalled(tool_id.to_string()))?,
+    };
+    if !source.exists() {
+        return Err(LauncherError::InvalidInput(format!(
+            "{} does not exist",
+            source.display()
+        )));
+    }
+
+    let version = probe_installed_version(&source).ok_or_else(|| {
+        LauncherError::InvalidInput(format!(
+            "Could not read a version from {}",
+            source.display()
+        ))
+    })?;
+
+    if !migrate {
+        update_config(|config| {
+            config.tools.insert(
+                tool_id.to_string(),
+                ToolRecord {
+                    install_path: Some(source.clone()),
+                    ..ToolRecord::new(&version)
+                },
+            )
+        })?;
+        write_version_marker(tool_id, &version);
+        return Ok(format!(
+            "Using {} {} at {}",
+            tool.name,
+            version,
+            source.display()
+        ));
+    }
+
+    // Moving an app out from under itself, or replacing the managed copy
+    // while it runs, breaks whichever copy is running
+    if is_tool_running(tool_id) || is_path_running(&source) {
+        return Err(LauncherError::ToolRunning(tool_id.to_string()));
+    }
+
+    // Set the managed copy aside; it's put back if the move or the record fails
+    ensure_dirs().map_err(|e| LauncherError::from_io("Failed to create apps folder", e))?;
+    let dest = get_app_path(tool.app_name);
+    let previous = SetAside::new(&get_apps_dir(), tool.app_name)?;
+
+    let left_behind = match move_app(&source, &dest) {
+        Ok(left_behind) => left_behind,
+        Err(e) => {
+            previous.restore();
+            return Err(e);
+        }
+    };
+    if let Err(e) = update_config(|config| {
+        config
+            .tools
+            .insert(tool_id.to_string(), ToolRecord::new(&version))
+    }) {
+        // Put the adopted app back where it came from. If that fails too, it
+        // stays in the apps folder rather than being lost.
+        if left_behind || move_app(&dest, &source).is_ok() {
+            previous.restore();
+        }
+        return Err(e);
+    }
+    previous.discard();
+    write_version_marker(tool_id, &version);
+
+    if left_behind {
+        Ok(format!(
+            "Copied {} {} into the launcher; the original at {} could not be removed",
+            tool.name,
+            version,
+            source.display()
+        ))
+    } else {
+        Ok(format!("Moved {} {} into the launcher", tool.name, version))
+    }
+}
+
+// Returns true when the app had to be copied and the original couldn't be removed
+fn move_app(source: &Path, dest: &Path) -> Result<bool, LauncherError> {
+    if fs::rename(source, dest).is_ok() {
+        return Ok(false);
+    }
+
+    // Different volume: copy with ditto to keep bundle metadata and signatures intact
+    let output = Command::new("ditto")
+        .arg(source)
+        .arg(dest)
+        .output()
+        .map_err(|e| LauncherError::from_io("Failed to copy app", e))?;
+    if !output.status.success() {
+        let _ = fs::remove_dir_all(dest);
+        return Err(LauncherError::Io(format!(
+            "Failed to copy app: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(fs::remove_dir_all(source).is_err())
+}
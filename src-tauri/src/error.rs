@@ -0,0 +1,147 @@
+// Typed launcher errors, sent to the frontend as `{ code, message, ... }`
+// so the UI can branch on `code` and show `message` as-is
+
+use std::fmt;
+use std::io;
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+use crate::now_secs;
+
+#[derive(Debug, Clone)]
+pub enum LauncherError {
+    Network(String),
+    RateLimited {
+        reset: Option<u64>, // unix timestamp when the GitHub quota resets
+    },
+    NotFound(String),
+    ChecksumMismatch {
+        file: String,
+        expected: String,
+        actual: String,
+    },
+    Extraction(String),
+    Permission(String),
+    DiskFull(String),
+    UnknownTool(String),
+    UnknownWebApp(String),
+    NotInstalled(String),
+    ToolRunning(String), // tool_id; replacing or removing it now would break the running app
+    Installing(String),  // tool_id; another install of the same tool is in progress
+    Dependency(String),  // unsatisfiable or still-needed tool dependency
+    InvalidInput(String),
+    Config(String),
+    NewerConfig(String), // written by a newer launcher; never recovered or overwritten
+    Launch(String),
+    Io(String),
+}
+
+impl LauncherError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            LauncherError::Network(_) => "network",
+            LauncherError::RateLimited { .. } => "rate_limited",
+            LauncherError::NotFound(_) => "not_found",
+            LauncherError::ChecksumMismatch { .. } => "checksum_mismatch",
+            LauncherError::Extraction(_) => "extraction",
+            LauncherError::Permission(_) => "permission",
+            LauncherError::DiskFull(_) => "disk_full",
+            LauncherError::UnknownTool(_) => "unknown_tool",
+            LauncherError::UnknownWebApp(_) => "unknown_web_app",
+            LauncherError::NotInstalled(_) => "not_installed",
+            LauncherError::ToolRunning(_) => "tool_running",
+            LauncherError::Installing(_) => "installing",
+            LauncherError::Dependency(_) => "dependency",
+            LauncherError::InvalidInput(_) => "invalid_input",
+            LauncherError::Config(_) => "config",
+            LauncherError::NewerConfig(_) => "newer_config",
+            LauncherError::Launch(_) => "launch",
+            LauncherError::Io(_) => "io",
+        }
+    }
+
+    // Classify an I/O failure so permission and disk space problems stand out
+    pub fn from_io(context: &str, e: io::Error) -> Self {
+        let message = format!("{}: {}", context, e);
+        match e.kind() {
+            io::ErrorKind::PermissionDenied | io::ErrorKind::ReadOnlyFilesystem => {
+                LauncherError::Permission(message)
+            }
+            io::ErrorKind::StorageFull | io::ErrorKind::QuotaExceeded => {
+                LauncherError::DiskFull(message)
+            }
+            _ => LauncherError::Io(message),
+        }
+    }
+
+    pub fn network(context: &str, e: reqwest::Error) -> Self {
+        LauncherError::Network(format!("{}: {}", context, e))
+    }
+}
+
+impl fmt::Display for LauncherError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LauncherError::RateLimited { reset } => {
+                let now = now_secs();
+                match reset {
+                    Some(reset) if *reset > now => write!(
+                        f,
+                        "GitHub API rate limit exceeded. Try again in {} minutes.",
+                        (reset - now).div_ceil(60)
+                    ),
+                    _ => write!(f, "GitHub API rate limit exceeded. Please try again later."),
+                }
+            }
+            LauncherError::ChecksumMismatch {
+                file,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Checksum mismatch for {}: expected {}, got {}",
+                file, expected, actual
+            ),
+            LauncherError::UnknownTool(tool_id) => write!(f, "Unknown tool: {}", tool_id),
+            LauncherError::UnknownWebApp(id) => write!(f, "Unknown web app: {}", id),
+            LauncherError::NotInstalled(tool_id) => write!(f, "{} is not installed", tool_id),
+            LauncherError::ToolRunning(tool_id) => {
+                write!(f, "{} is running. Quit it and try again.", tool_id)
+            }
+            LauncherError::Installing(tool_id) => {
+                write!(f, "{} is already being installed", tool_id)
+            }
+            LauncherError::Network(message)
+            | LauncherError::NotFound(message)
+            | LauncherError::Extraction(message)
+            | LauncherError::Permission(message)
+            | LauncherError::DiskFull(message)
+            | LauncherError::Dependency(message)
+            | LauncherError::InvalidInput(message)
+            | LauncherError::Config(message)
+            | LauncherError::NewerConfig(message)
+            | LauncherError::Launch(message)
+            | LauncherError::Io(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for LauncherError {}
+
+impl Serialize for LauncherError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("LauncherError", 3)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        match self {
+            LauncherError::RateLimited { reset } => state.serialize_field("reset", reset)?,
+            LauncherError::UnknownTool(tool_id)
+            | LauncherError::NotInstalled(tool_id)
+            | LauncherError::ToolRunning(tool_id)
+            | LauncherError::Installing(tool_id) => state.serialize_field("tool_id", tool_id)?,
+            _ => {}
+        }
+        state.end()
+    }
+}
@@ -0,0 +1,917 @@
+// Install engine shared by the GUI commands and the headless CLI
+
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use tar::Archive;
+
+use crate::bundle;
+use crate::config::ToolRecord;
+use crate::dependencies::{self, InstallStep};
+use crate::discovery::{self, InstallSource};
+use crate::error::LauncherError;
+use crate::logging;
+use crate::process::is_tool_running;
+use crate::resolve;
+use crate::tools::{find_tool, ArtifactKind, ToolDefinition};
+use crate::{
+    ensure_dirs, get_app_path, get_apps_dir, get_installed_version, is_tool_installed, load_config,
+    tool_app_path, update_config, ToolStatus,
+};
+
+// Tools with an install in progress. Two installs of one tool would share the
+// download and the backup of the previous app, so the second is refused.
+static INSTALLING: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+struct InstallGuard(String);
+
+impl InstallGuard {
+    fn acquire(tool_id: &str) -> Result<Self, LauncherError> {
+        let mut installing = INSTALLING.lock().unwrap();
+        if installing.iter().any(|id| id == tool_id) {
+            return Err(LauncherError::Installing(tool_id.to_string()));
+        }
+        installing.push(tool_id.to_string());
+        Ok(InstallGuard(tool_id.to_string()))
+    }
+}
+
+impl Drop for InstallGuard {
+    fn drop(&mut self) {
+        INSTALLING.lock().unwrap().retain(|id| *id != self.0);
+    }
+}
+
+// What an install did beyond the tool itself
+#[derive(Debug, Clone)]
+pub struct Installed {
+    pub message: String,
+    pub dependencies: Vec<InstallStep>, // installed first, in plan order
+}
+
+impl Installed {
+    // One line for the CLI and logs; the window lists dependencies itself
+    pub fn summary(&self) -> String {
+        if self.dependencies.is_empty() {
+            return self.message.clone();
+        }
+        let names: Vec<String> = self
+            .dependencies
+            .iter()
+            .map(|step| format!("{} {}", step.tool_id, step.version))
+            .collect();
+        format!("{} (also installed {})", self.message, names.join(", "))
+    }
+}
+
+// An app moved out of the apps folder while it's replaced, so it can be put
+// back if the replacement fails
+pub struct SetAside {
+    app_path: PathBuf,
+    previous_path: PathBuf,
+}
+
+impl SetAside {
+    pub fn new(apps_dir: &Path, app_name: &str) -> Result<Self, LauncherError> {
+        let set_aside = SetAside {
+            app_path: apps_dir.join(app_name),
+            previous_path: apps_dir.join(format!(".{}.previous", app_name)),
+        };
+        remove_path(&set_aside.previous_path);
+        if set_aside.app_path.exists() {
+            fs::rename(&set_aside.app_path, &set_aside.previous_path)
+                .map_err(|e| LauncherError::from_io("Failed to remove existing app", e))?;
+        }
+        Ok(set_aside)
+    }
+
+    // Remove whatever replaced the app and put the old one back
+    pub fn restore(&self) {
+        remove_path(&self.app_path);
+        if self.previous_path.exists() {
+            let _ = fs::rename(&self.previous_path, &self.app_path);
+        }
+    }
+
+    pub fn discard(&self) {
+        remove_path(&self.previous_path);
+    }
+}
+
+// Apps are bundles or folders, but some tools are a single executable
+fn remove_path(path: &Path) {
+    if path.is_dir() {
+        let _ = fs::remove_dir_all(path);
+    } else {
+        let _ = fs::remove_file(path);
+    }
+}
+
+// GitHub API response types
+#[derive(Debug, Deserialize)]
+struct GitHubRelease {
+    tag_name: String,
+    assets: Vec<GitHubAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitHubAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+pub fn tool_status(tool_id: &str) -> ToolStatus {
+    let tool = match find_tool(tool_id) {
+        Some(tool) => tool,
+        None => {
+            return ToolStatus {
+                installed: false,
+                installed_version: None,
+                recorded_version: None,
+                version_mismatch: false,
+                source: None,
+                discovered_path: None,
+                latest_version: None,
+                has_update: false,
+                running: false,
+                update_queued: false,
+                error: Some(LauncherError::UnknownTool(tool_id.to_string())),
+            }
+        }
+    };
+
+    // Trust the app on disk over the record, which goes stale when someone
+    // replaces the app by hand or the config is lost
+    let recorded_version = get_installed_version(tool_id);
+    let detected_version = bundle::detect_tool_version(tool);
+    let installed = is_tool_installed(tool_id) || detected_version.is_some();
+    let version_mismatch = match (&recorded_version, &detected_version) {
+        (Some(recorded), Some(detected)) => !bundle::versions_match(recorded, detected),
+        (None, Some(_)) => true,
+        _ => false,
+    };
+    if version_mismatch {
+        logging::info(
+            "version_mismatch",
+            json!({ "tool_id": tool_id, "recorded": recorded_version, "detected": detected_version }),
+        );
+    }
+    let installed_version = detected_version.or_else(|| recorded_version.clone());
+    let source = discovery::install_source(tool_id).filter(|_| installed);
+    let discovered_path = discovery::discover_installations(tool)
+        .into_iter()
+        .next()
+        .filter(|_| !installed);
+    let running = installed && is_tool_running(tool_id);
+
+    // Fetch latest release from GitHub
+    let started = Instant::now();
+    let release = get_latest_release(tool.repo);
+    let fields = json!({ "tool_id": tool_id, "duration_ms": elapsed_ms(started) });
+    match &release {
+        Ok(release) => logging::info("check", with_field(fields, "latest_tag", &release.tag_name)),
+        Err(e) => logging::error("check", e, fields),
+    }
+
+    match release {
+        Ok(release) => {
+            let latest_version = release.tag_name.trim_start_matches('v').to_string();
+            // Compare against what we installed, not the bundle's own version: the
+            // two can differ in format or go unbumped, which `version_mismatch`
+            // already reports, and would otherwise reinstall on every check
+            let has_update = installed
+                && recorded_version
+                    .as_ref()
+                    .or(installed_version.as_ref())
+                    .map(|v| !bundle::versions_match(v, &latest_version))
+                    .unwrap_or(false);
+
+            ToolStatus {
+                installed,
+                installed_version,
+                recorded_version,
+                version_mismatch,
+                source,
+                discovered_path,
+                latest_version: Some(latest_version),
+                has_update,
+                running,
+                update_queued: false,
+                error: None,
+            }
+        }
+        Err(e) => ToolStatus {
+            installed,
+            installed_version,
+            recorded_version,
+            version_mismatch,
+            source,
+            discovered_path,
+            latest_version: None,
+            has_update: false,
+            running,
+            update_queued: false,
+            error: Some(e),
+        },
+    }
+}
+
+// Returns a human-readable summary on success
+pub fn install(tool_id: &str, version: Option<&str>) -> Result<Installed, LauncherError> {
+    let started = Instant::now();
+    logging::info(
+        "install_started",
+        json!({ "tool_id": tool_id, "requested_version": version }),
+    );
+
+    let result = install_dependencies(tool_id).and_then(|dependencies| {
+        Ok(Installed {
+            message: install_release(tool_id, version)?,
+            dependencies,
+        })
+    });
+    let fields = json!({ "tool_id": tool_id, "duration_ms": elapsed_ms(started) });
+    match &result {
+        Ok(installed) => logging::info(
+            "install_completed",
+            with_field(fields, "message", installed.summary()),
+        ),
+        Err(e) => logging::error("install_failed", e, fields),
+    }
+    result
+}
+
+// Install whatever the plan says is missing or too old, returning the steps
+// that were installed
+fn install_dependencies(tool_id: &str) -> Result<Vec<InstallStep>, LauncherError> {
+    let plan = dependencies::install_plan(tool_id)?;
+    let mut installed = Vec::new();
+
+    for step in plan {
+        let fields = json!({
+            "tool_id": step.tool_id,
+            "version": step.version,
+            "required_by": step.required_by,
+        });
+        match install_release(&step.tool_id, Some(&step.version)) {
+            Ok(_) => logging::info("dependency_install", fields),
+            Err(e) => {
+                logging::error("dependency_install", &e, fields);
+                return Err(e);
+            }
+        }
+        installed.push(step);
+    }
+
+    Ok(installed)
+}
+
+fn install_release(tool_id: &str, version: Option<&str>) -> Result<String, LauncherError> {
+    let tool = find_tool(tool_id).ok_or_else(|| LauncherError::UnknownTool(tool_id.to_string()))?;
+    let _guard = InstallGuard::acquire(tool_id)?;
+
+    // Replacing the app under a running tool can corrupt whatever it's doing
+    if is_tool_running(tool_id) {
+        return Err(LauncherError::ToolRunning(tool_id.to_string()));
+    }
+    // A fresh copy in the apps folder would quietly replace the adopted one and
+    // leave it behind, so it has to be moved into the launcher first
+    if discovery::install_source(tool_id) == Some(InstallSource::External) {
+        let path = tool_app_path(tool_id).unwrap_or_default();
+        return Err(LauncherError::InvalidInput(format!(
+            "{} is installed at {}, outside the launcher. Move it into the launcher to update it.",
+            tool.name,
+            path.display()
+        )));
+    }
+
+    // Ensure directories exist
+    ensure_dirs().map_err(|e| LauncherError::from_io("Failed to create directories", e))?;
+
+    // Get the requested release, or the latest one
+    let release = get_release(tool.repo, version)?;
+    dependencies::check_dependents(tool_id, release.tag_name.trim_start_matches('v'))?;
+    if let ArtifactKind::ResolveScript { category } = tool.kind {
+        return install_scripts(tool, category, &release);
+    }
+
+    // Find downloadable asset
+    let asset = find_app_asset(&release).ok_or_else(|| {
+        LauncherError::NotFound("No compatible download found in release".to_string())
+    })?;
+
+    // Download to temp file
+    let temp_dir = std::env::temp_dir();
+    let temp_file = temp_dir.join(&asset.name);
+
+    download_file(&asset.browser_download_url, &temp_file)?;
+    if let Err(e) = verify_checksum(&release, asset, &temp_file) {
+        let _ = fs::remove_file(&temp_file);
+        return Err(e);
+    }
+
+    // Move the existing app aside, unless the tool was started during the download.
+    // It's put back if anything below fails.
+    let app_path = get_app_path(tool.app_name);
+    if is_tool_running(tool_id) {
+        let _ = fs::remove_file(&temp_file);
+        return Err(LauncherError::ToolRunning(tool_id.to_string()));
+    }
+    let apps_dir = get_apps_dir();
+    let previous = match SetAside::new(&apps_dir, tool.app_name) {
+        Ok(previous) => previous,
+        Err(e) => {
+            let _ = fs::remove_file(&temp_file);
+            return Err(e);
+        }
+    };
+
+    // Extract based on file type
+    let started = Instant::now();
+    let result = if asset.name.ends_with(".tar.gz") {
+        extract_tar_gz(&temp_file, &apps_dir)
+    } else if asset.name.ends_with(".zip") {
+        extract_zip(&temp_file, &apps_dir)
+    } else if asset.name.ends_with(".dmg") {
+        // For DMG, we need to mount, copy, and unmount
+        extract_from_dmg(&temp_file, &apps_dir, tool.app_name)
+    } else {
+        Err(LauncherError::Extraction(
+            "Unsupported archive format".to_string(),
+        ))
+    };
+
+    // Clean up temp file
+    let _ = fs::remove_file(&temp_file);
+    let fields = json!({ "archive": asset.name, "duration_ms": elapsed_ms(started) });
+    match &result {
+        Ok(()) => logging::info("extract", fields),
+        Err(e) => logging::error("extract", e, fields),
+    }
+    if let Err(e) = result {
+        previous.restore();
+        return Err(e);
+    }
+
+    // Remove quarantine attribute
+    let _ = Command::new("xattr")
+        .args(["-cr", app_path.to_str().unwrap_or("")])
+        .output();
+
+    // Update config
+    let version = release.tag_name.trim_start_matches('v').to_string();
+    if let Err(e) = update_config(|config| {
+        config
+            .tools
+            .insert(tool_id.to_string(), ToolRecord::new(&version))
+    }) {
+        previous.restore();
+        return Err(e);
+    }
+    previous.discard();
+    bundle::write_version_marker(tool_id, &version);
+
+    Ok(format!("Installed version {}", version))
+}
+
+// Resolve scripts are copied straight into Resolve's folders. The previous
+// version's files are set aside until the new ones are recorded.
+fn install_scripts(
+    tool: &ToolDefinition,
+    category: &str,
+    release: &GitHubRelease,
+) -> Result<String, LauncherError> {
+    let files: Vec<(&GitHubAsset, PathBuf)> = release
+        .assets
+        .iter()
+        .filter_map(|asset| Some((asset, resolve::destination(&asset.name, category)?)))
+        .collect();
+    if files.is_empty() {
+        return Err(LauncherError::NotFound(
+            "No Resolve scripts found in release".to_string(),
+        ));
+    }
+
+    // Download and verify everything before touching Resolve's folders
+    let temp_dir = std::env::temp_dir();
+    let mut downloads: Vec<(PathBuf, PathBuf)> = Vec::new();
+    for (asset, dest) in files {
+        let temp_file = temp_dir.join(&asset.name);
+        let result = download_file(&asset.browser_download_url, &temp_file)
+            .and_then(|_| verify_checksum(release, asset, &temp_file));
+        downloads.push((temp_file, dest));
+        if let Err(e) = result {
+            for (temp_file, _) in &downloads {
+                let _ = fs::remove_file(temp_file);
+            }
+            return Err(e);
+        }
+    }
+
+    let previous = load_config()
+        .tools
+        .get(tool.id)
+        .map(|record| record.files.clone())
+        .unwrap_or_default();
+    let mut scripts = ScriptReplacement::default();
+    let result = scripts.replace(&previous, &downloads);
+    for (temp_file, _) in &downloads {
+        let _ = fs::remove_file(temp_file);
+    }
+    if let Err(e) = result {
+        scripts.restore();
+        return Err(e);
+    }
+    logging::info(
+        "install_scripts",
+        json!({ "tool_id": tool.id, "files": scripts.installed }),
+    );
+
+    let version = release.tag_name.trim_start_matches('v').to_string();
+    if let Err(e) = update_config(|config| {
+        config.tools.insert(
+            tool.id.to_string(),
+            ToolRecord {
+                files: scripts.installed.clone(),
+                ..ToolRecord::new(&version)
+            },
+        )
+    }) {
+        scripts.restore();
+        return Err(e);
+    }
+    scripts.discard();
+    bundle::write_version_marker(tool.id, &version);
+
+    Ok(format!("Installed version {}", version))
+}
+
+// The files a script install moved aside and copied in, so a failure at any
+// point can be undone
+#[derive(Default)]
+struct ScriptReplacement {
+    set_aside: Vec<(PathBuf, PathBuf)>, // (path, backup)
+    installed: Vec<PathBuf>,
+}
+
+impl ScriptReplacement {
+    // Moves the old files, and anything else in the way, aside before copying
+    // the new ones in
+    fn replace(
+        &mut self,
+        previous: &[PathBuf],
+        downloads: &[(PathBuf, PathBuf)],
+    ) -> Result<(), LauncherError> {
+        let in_the_way = downloads.iter().map(|(_, dest)| dest);
+        for path in previous.iter().chain(in_the_way) {
+            if !path.exists() || self.set_aside.iter().any(|(p, _)| p == path) {
+                continue;
+            }
+            let mut backup = path.as_os_str().to_os_string();
+            backup.push(".previous");
+            let backup = PathBuf::from(backup);
+            fs::rename(path, &backup)
+                .map_err(|e| LauncherError::from_io("Failed to move previous script aside", e))?;
+            self.set_aside.push((path.clone(), backup));
+        }
+
+        for (temp_file, dest) in downloads {
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| LauncherError::from_io("Failed to create Resolve folder", e))?;
+            }
+            self.installed.push(dest.clone());
+            fs::copy(temp_file, dest)
+                .map_err(|e| LauncherError::from_io("Failed to install script", e))?;
+        }
+        Ok(())
+    }
+
+    // Remove the new files and put the old ones back
+    fn restore(&self) {
+        for path in &self.installed {
+            let _ = fs::remove_file(path);
+        }
+        for (path, backup) in &self.set_aside {
+            let _ = fs::rename(backup, path);
+        }
+    }
+
+    fn discard(&self) {
+        for (_, backup) in &self.set_aside {
+            let _ = fs::remove_file(backup);
+        }
+    }
+}
+
+fn extract_from_dmg(dmg_path: &Path, dest_dir: &Path, app_name: &str) -> Result<(), LauncherError> {
+    // Mount DMG
+    let output = Command::new("hdiutil")
+        .args(["attach", dmg_path.to_str().unwrap(), "-nobrowse", "-quiet"])
+        .output()
+        .map_err(|e| LauncherError::Extraction(format!("Failed to mount DMG: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(LauncherError::Extraction("Failed to mount DMG".to_string()));
+    }
+
+    // Find mount point
+    let output = Command::new("hdiutil")
+        .args(["info", "-plist"])
+        .output()
+        .map_err(|e| LauncherError::Extraction(format!("Failed to get mount info: {}", e)))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mount_point = stdout
+        .lines()
+        .find(|l| l.contains("/Volumes/"))
+        .and_then(|l| l.split("<string>").nth(1))
+        .and_then(|l| l.split("</string>").next())
+        .ok_or_else(|| LauncherError::Extraction("Failed to find mount point".to_string()))?
+        .to_string();
+
+    // Copy app
+    let src = PathBuf::from(&mount_point).join(app_name);
+    let dest = dest_dir.join(app_name);
+
+    let copy_result = Command::new("cp")
+        .args(["-R", src.to_str().unwrap(), dest.to_str().unwrap()])
+        .output();
+
+    // Unmount DMG
+    let _ = Command::new("hdiutil")
+        .args(["detach", &mount_point, "-quiet"])
+        .output();
+
+    copy_result
+        .map_err(|e| LauncherError::from_io("Failed to copy app", e))
+        .and_then(|o| {
+            if o.status.success() {
+                Ok(())
+            } else {
+                Err(LauncherError::Extraction(
+                    "Failed to copy app from DMG".to_string(),
+                ))
+            }
+        })
+}
+
+// Newest first, as returned by GitHub
+pub fn list_versions(tool_id: &str) -> Result<Vec<String>, LauncherError> {
+    let tool = find_tool(tool_id).ok_or_else(|| LauncherError::UnknownTool(tool_id.to_string()))?;
+
+    let response = github_get(&format!(
+        "https://api.github.com/repos/{}/releases",
+        tool.repo
+    ))?;
+
+    let releases = response
+        .json::<Vec<GitHubRelease>>()
+        .map_err(|e| LauncherError::network("Failed to parse releases", e))?;
+
+    Ok(releases
+        .into_iter()
+        .map(|release| release.tag_name.trim_start_matches('v').to_string())
+        .collect())
+}
+
+fn get_latest_release(repo: &str) -> Result<GitHubRelease, LauncherError> {
+    fetch_release(&format!(
+        "https://api.github.com/repos/{}/releases/latest",
+        repo
+    ))
+}
+
+// Release tags are usually `v1.2.3`, but fall back to the bare version
+fn get_release(repo: &str, version: Option<&str>) -> Result<GitHubRelease, LauncherError> {
+    let version = match version {
+        Some(version) => version.trim_start_matches('v'),
+        None => return get_latest_release(repo),
+    };
+
+    let tag_url = |tag: &str| {
+        format!(
+            "https://api.github.com/repos/{}/releases/tags/{}",
+            repo, tag
+        )
+    };
+    fetch_release(&tag_url(&format!("v{}", version))).or_else(|e| match e {
+        LauncherError::NotFound(_) => fetch_release(&tag_url(version)),
+        e => Err(e),
+    })
+}
+
+fn fetch_release(url: &str) -> Result<GitHubRelease, LauncherError> {
+    github_get(url)?
+        .json::<GitHubRelease>()
+        .map_err(|e| LauncherError::network("Failed to parse release info", e))
+}
+
+fn github_get(url: &str) -> Result<reqwest::blocking::Response, LauncherError> {
+    let client = reqwest::blocking::Client::builder()
+        .user_agent("Story-Launcher/1.0")
+        .build()
+        .map_err(|e| LauncherError::network("Failed to create HTTP client", e))?;
+
+    let response = client
+        .get(url)
+        .send()
+        .map_err(|e| LauncherError::network("Failed to fetch release info", e))?;
+
+    if response.status() == 403 || response.status() == 429 {
+        let reset = response
+            .headers()
+            .get("x-ratelimit-reset")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok());
+        return Err(LauncherError::RateLimited { reset });
+    }
+
+    if response.status() == 404 {
+        return Err(LauncherError::NotFound(
+            "No releases found for this repository.".to_string(),
+        ));
+    }
+
+    if !response.status().is_success() {
+        return Err(LauncherError::Network(format!(
+            "GitHub API error: {}",
+            response.status()
+        )));
+    }
+
+    Ok(response)
+}
+
+fn find_app_asset(release: &GitHubRelease) -> Option<&GitHubAsset> {
+    // Look for .app.tar.gz first (preferred), then .app.zip, then .dmg
+    release
+        .assets
+        .iter()
+        .find(|a| a.name.ends_with(".app.tar.gz"))
+        .or_else(|| release.assets.iter().find(|a| a.name.ends_with(".app.zip")))
+        .or_else(|| release.assets.iter().find(|a| a.name.ends_with(".dmg")))
+}
+
+// Releases may publish `<asset>.sha256` next to the download; verify against it when present
+fn verify_checksum(
+    release: &GitHubRelease,
+    asset: &GitHubAsset,
+    file_path: &Path,
+) -> Result<(), LauncherError> {
+    let checksum_name = format!("{}.sha256", asset.name);
+    let Some(checksum_asset) = release.assets.iter().find(|a| a.name == checksum_name) else {
+        return Ok(());
+    };
+
+    let checksum_file = file_path.with_file_name(&checksum_name);
+    download_file(&checksum_asset.browser_download_url, &checksum_file)?;
+    let contents = fs::read_to_string(&checksum_file);
+    let _ = fs::remove_file(&checksum_file);
+
+    // `shasum -a 256` format: "<hex>  <file name>"
+    let expected = contents
+        .map_err(|e| LauncherError::from_io("Failed to read checksum", e))?
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+
+    let mut file =
+        File::open(file_path).map_err(|e| LauncherError::from_io("Failed to open download", e))?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)
+        .map_err(|e| LauncherError::from_io("Failed to read download", e))?;
+    let actual = format!("{:x}", hasher.finalize());
+
+    if actual != expected {
+        return Err(LauncherError::ChecksumMismatch {
+            file: asset.name.clone(),
+            expected,
+            actual,
+        });
+    }
+
+    Ok(())
+}
+
+fn download_file(url: &str, dest: &Path) -> Result<(), LauncherError> {
+    let started = Instant::now();
+    let result = download_to(url, dest);
+    let fields = json!({ "url": url, "duration_ms": elapsed_ms(started) });
+    match &result {
+        Ok(bytes) => logging::info("download", with_field(fields, "bytes", bytes)),
+        Err(e) => logging::error("download", e, fields),
+    }
+    result.map(|_| ())
+}
+
+// Returns the number of bytes written
+fn download_to(url: &str, dest: &Path) -> Result<usize, LauncherError> {
+    let client = reqwest::blocking::Client::builder()
+        .user_agent("Story-Launcher/1.0")
+        .build()
+        .map_err(|e| LauncherError::network("Failed to create HTTP client", e))?;
+
+    let response = client
+        .get(url)
+        .send()
+        .map_err(|e| LauncherError::network("Failed to download", e))?;
+
+    if response.status() == 404 {
+        return Err(LauncherError::NotFound(format!(
+            "Download not found: {}",
+            url
+        )));
+    }
+
+    if !response.status().is_success() {
+        return Err(LauncherError::Network(format!(
+            "Download failed: {}",
+            response.status()
+        )));
+    }
+
+    let bytes = response
+        .bytes()
+        .map_err(|e| LauncherError::network("Failed to read download", e))?;
+
+    let mut file =
+        File::create(dest).map_err(|e| LauncherError::from_io("Failed to create file", e))?;
+
+    file.write_all(&bytes)
+        .map_err(|e| LauncherError::from_io("Failed to write file", e))?;
+
+    Ok(bytes.len())
+}
+
+fn elapsed_ms(started: Instant) -> u64 {
+    started.elapsed().as_millis() as u64
+}
+
+fn with_field(mut fields: Value, key: &str, value: impl Serialize) -> Value {
+    fields[key] = json!(value);
+    fields
+}
+
+fn extract_tar_gz(archive_path: &Path, dest_dir: &Path) -> Result<(), LauncherError> {
+    let file = File::open(archive_path)
+        .map_err(|e| LauncherError::from_io("Failed to open archive", e))?;
+
+    let decoder = GzDecoder::new(file);
+    let mut archive = Archive::new(decoder);
+
+    archive.unpack(dest_dir).map_err(|e| match e.kind() {
+        io::ErrorKind::PermissionDenied | io::ErrorKind::StorageFull => {
+            LauncherError::from_io("Failed to extract archive", e)
+        }
+        _ => LauncherError::Extraction(format!("Failed to extract archive: {}", e)),
+    })?;
+
+    Ok(())
+}
+
+fn extract_zip(archive_path: &Path, dest_dir: &Path) -> Result<(), LauncherError> {
+    let file = File::open(archive_path)
+        .map_err(|e| LauncherError::from_io("Failed to open archive", e))?;
+
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| LauncherError::Extraction(format!("Failed to read zip: {}", e)))?;
+
+    for i in 0..archive.len() {
+        let mut file = archive
+            .by_index(i)
+            .map_err(|e| LauncherError::Extraction(format!("Failed to read zip entry: {}", e)))?;
+
+        let outpath = dest_dir.join(file.name());
+
+        if file.name().ends_with('/') {
+            fs::create_dir_all(&outpath)
+                .map_err(|e| LauncherError::from_io("Failed to create directory", e))?;
+        } else {
+            if let Some(parent) = outpath.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| LauncherError::from_io("Failed to create directory", e))?;
+            }
+            let mut outfile = File::create(&outpath)
+                .map_err(|e| LauncherError::from_io("Failed to create file", e))?;
+            io::copy(&mut file, &mut outfile)
+                .map_err(|e| LauncherError::from_io("Failed to write file", e))?;
+        }
+
+        // Set permissions on Unix
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            if let Some(mode) = file.unix_mode() {
+                fs::set_permissions(&outpath, fs::Permissions::from_mode(mode)).ok();
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn write(path: &Path, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    fn read(path: &Path) -> Option<String> {
+        fs::read_to_string(path).ok()
+    }
+
+    // Everything left in a folder, for checking no backups are left behind
+    fn files_in(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn replaces_recorded_scripts() {
+        let dir = TempDir::new("installer-replace");
+        let scripts = dir.join("Fusion/Scripts/Utility");
+        let templates = dir.join("Fusion/Templates");
+        write(&scripts.join("Sync.py"), "1.0");
+        write(&scripts.join("Old.py"), "1.0");
+        write(&dir.join("download/Sync.py"), "2.0");
+        write(&dir.join("download/Titles.drfx"), "2.0");
+
+        let previous = [scripts.join("Sync.py"), scripts.join("Old.py")];
+        let downloads = [
+            (dir.join("download/Sync.py"), scripts.join("Sync.py")),
+            (
+                dir.join("download/Titles.drfx"),
+                templates.join("Titles.drfx"),
+            ),
+        ];
+        let mut replacement = ScriptReplacement::default();
+        replacement.replace(&previous, &downloads).unwrap();
+        replacement.discard();
+
+        assert_eq!(
+            replacement.installed,
+            [scripts.join("Sync.py"), templates.join("Titles.drfx")]
+        );
+        assert_eq!(read(&scripts.join("Sync.py")).as_deref(), Some("2.0"));
+        assert_eq!(read(&templates.join("Titles.drfx")).as_deref(), Some("2.0"));
+        // A file the new version dropped is removed
+        assert_eq!(files_in(&scripts), ["Sync.py"]);
+        assert_eq!(files_in(&templates), ["Titles.drfx"]);
+    }
+
+    #[test]
+    fn failed_replace_restores_previous_scripts() {
+        let dir = TempDir::new("installer-restore");
+        let scripts = dir.join("Fusion/Scripts/Utility");
+        let templates = dir.join("Fusion/Templates");
+        write(&scripts.join("Sync.py"), "1.0");
+        // Not recorded, but in the way of the new version
+        write(&templates.join("Titles.drfx"), "mine");
+        write(&dir.join("download/Sync.py"), "2.0");
+
+        let previous = [scripts.join("Sync.py")];
+        let downloads = [
+            (dir.join("download/Sync.py"), scripts.join("Sync.py")),
+            (
+                dir.join("download/Titles.drfx"),
+                templates.join("Titles.drfx"),
+            ),
+            (
+                dir.join("download/Missing.lua"),
+                scripts.join("Missing.lua"),
+            ),
+        ];
+        let mut replacement = ScriptReplacement::default();
+        assert!(replacement.replace(&previous, &downloads).is_err());
+        replacement.restore();
+
+        assert_eq!(read(&scripts.join("Sync.py")).as_deref(), Some("1.0"));
+        assert_eq!(
+            read(&templates.join("Titles.drfx")).as_deref(),
+            Some("mine")
+        );
+        assert_eq!(files_in(&scripts), ["Sync.py"]);
+        assert_eq!(files_in(&templates), ["Titles.drfx"]);
+    }
+}
@@ -1,61 +1,148 @@
 use std::collections::HashMap;
-use std::fs::{self, File};
-use std::io::{self, Read, Write};
+use std::fs;
+use std::io;
 use std::path::PathBuf;
 use std::process::Command;
-use std::sync::Mutex;
+use std::sync::{mpsc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
 
-use flate2::read::GzDecoder;
-use serde::{Deserialize, Serialize};
-use tar::Archive;
+use serde::Serialize;
+use serde_json::json;
 use tauri::{
     image::Image,
-    menu::{Menu, MenuItem, PredefinedMenuItem},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    Emitter, Manager, Runtime,
+    Manager, Runtime,
 };
 
-// Tool configuration
-const RESOLVE_SYNC_REPO: &str = "joyrider00/spellbook-resolve-sync";
-const RESOLVE_SYNC_APP_NAME: &str = "Spellbook Resolve Sync.app";
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
+mod bundle;
+pub mod cli;
+mod config;
+mod deep_link;
+mod dependencies;
+mod diagnostics;
+mod discovery;
+mod error;
+mod installer;
+mod location;
+mod logging;
+mod policy;
+mod process;
+mod profiles;
+mod recovery;
+mod resolve;
+mod scheduler;
+mod startup;
+mod supervisor;
+#[cfg(test)]
+mod test_support;
+mod tool_logs;
+mod tools;
+mod tray;
+mod webview;
+
+use config::{load_config, read_config, update_config};
+use deep_link::{handle_deep_link, PendingInstall};
+use dependencies::InstallStep;
+use discovery::InstallSource;
+use error::LauncherError;
+use installer::{install, tool_status, Installed};
+use process::UpdateQueue;
+use scheduler::SchedulerMessage;
+use supervisor::{LaunchOptions, SupervisedProcess};
+use tauri_plugin_deep_link::DeepLinkExt;
+use tools::{find_tool, merge_web_apps, web_app_id_from_name, ArtifactKind, WebApp};
+use tray::{create_tray_menu, refresh_tray, show_main_window, TRAY_ID};
+use webview::open_web_app_window;
+
+#[derive(Debug, Serialize, Clone)]
 pub struct ToolStatus {
     pub installed: bool,
-    pub installed_version: Option<String>,
+    pub installed_version: Option<String>, // detected from the installed app when possible
+    pub recorded_version: Option<String>,  // what config.json says was installed
+    pub version_mismatch: bool,            // the installed app doesn't match the record
+    pub source: Option<InstallSource>,     // None when not installed
+    pub discovered_path: Option<PathBuf>,  // an unmanaged install that could be adopted
     pub latest_version: Option<String>,
     pub has_update: bool,
-    pub error: Option<String>,
+    pub running: bool,
+    pub update_queued: bool, // will be installed once the tool exits
+    pub error: Option<LauncherError>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize)]
 pub struct ActionResult {
     pub success: bool,
     pub message: String,
+    pub error: Option<LauncherError>, // machine-readable details when `success` is false
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
-struct ToolsConfig {
-    #[serde(default)]
-    tools: HashMap<String, String>, // tool_id -> version
+impl From<Result<String, LauncherError>> for ActionResult {
+    fn from(result: Result<String, LauncherError>) -> Self {
+        match result {
+            Ok(message) => ActionResult {
+                success: true,
+                message,
+                error: None,
+            },
+            Err(e) => e.into(),
+        }
+    }
 }
 
-// GitHub API response types
-#[derive(Debug, Deserialize)]
-struct GitHubRelease {
-    tag_name: String,
-    assets: Vec<GitHubAsset>,
+impl From<LauncherError> for ActionResult {
+    fn from(error: LauncherError) -> Self {
+        ActionResult {
+            success: false,
+            message: error.to_string(),
+            error: Some(error),
+        }
+    }
 }
 
-#[derive(Debug, Deserialize)]
-struct GitHubAsset {
-    name: String,
-    browser_download_url: String,
+// ActionResult plus the dependencies an install pulled in, so the window can
+// list them
+#[derive(Debug, Serialize)]
+pub struct InstallResult {
+    pub success: bool,
+    pub message: String,
+    pub dependencies: Vec<InstallStep>,
+    pub error: Option<LauncherError>,
+}
+
+impl From<Result<Installed, LauncherError>> for InstallResult {
+    fn from(result: Result<Installed, LauncherError>) -> Self {
+        match result {
+            Ok(installed) => InstallResult {
+                success: true,
+                message: installed.message,
+                dependencies: installed.dependencies,
+                error: None,
+            },
+            Err(e) => e.into(),
+        }
+    }
+}
+
+impl From<LauncherError> for InstallResult {
+    fn from(error: LauncherError) -> Self {
+        InstallResult {
+            success: false,
+            message: error.to_string(),
+            dependencies: Vec::new(),
+            error: Some(error),
+        }
+    }
 }
 
 // Global state
 pub struct AppState {
     pub has_updates: Mutex<bool>,
+    pub available_updates: Mutex<HashMap<String, String>>, // tool_id -> latest version
+    pub pending_install: Mutex<Option<PendingInstall>>, // deep link install awaiting confirmation
+    pub launcher_update: Mutex<Option<String>>,         // newer launcher version, if any
+    pub scheduler: Mutex<Option<mpsc::Sender<SchedulerMessage>>>,
+    pub queued_updates: Mutex<UpdateQueue>, // tools waiting to exit before they update
+    pub supervised: Mutex<HashMap<String, SupervisedProcess>>, // tools the launcher started
 }
 
 fn get_tools_dir() -> PathBuf {
@@ -64,7 +151,11 @@ fn get_tools_dir() -> PathBuf {
 }
 
 fn get_apps_dir() -> PathBuf {
-    get_tools_dir().join("apps")
+    location::install_location().apps_dir()
+}
+
+fn get_tool_logs_dir(tool_id: &str) -> PathBuf {
+    logging::get_logs_dir().join(tool_id)
 }
 
 fn get_config_path() -> PathBuf {
@@ -76,430 +167,401 @@ fn ensure_dirs() -> io::Result<()> {
     Ok(())
 }
 
-fn load_config() -> ToolsConfig {
-    let config_path = get_config_path();
-    if config_path.exists() {
-        if let Ok(content) = fs::read_to_string(&config_path) {
-            if let Ok(config) = serde_json::from_str(&content) {
-                return config;
-            }
-        }
-    }
-    ToolsConfig::default()
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
 }
 
-fn save_config(config: &ToolsConfig) -> io::Result<()> {
-    ensure_dirs()?;
-    let config_path = get_config_path();
-    let content = serde_json::to_string_pretty(config)?;
-    fs::write(config_path, content)?;
-    Ok(())
+fn get_app_path(app_name: &str) -> PathBuf {
+    get_apps_dir().join(app_name)
 }
 
-fn get_latest_release(repo: &str) -> Result<GitHubRelease, String> {
-    let url = format!("https://api.github.com/repos/{}/releases/latest", repo);
+// Where a tool's app lives: its adopted external path, or the apps folder.
+// Resolve scripts live in Resolve's own folders instead.
+fn tool_app_path(tool_id: &str) -> Option<PathBuf> {
+    let tool = find_tool(tool_id)?;
+    let external = load_config()
+        .tools
+        .get(tool_id)
+        .and_then(|record| record.install_path.clone());
+    Some(
+        external
+            .or_else(|| resolve::script_path(tool))
+            .unwrap_or_else(|| get_app_path(tool.app_name)),
+    )
+}
 
-    let client = reqwest::blocking::Client::builder()
-        .user_agent("Story-Launcher/1.0")
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+fn is_tool_installed(tool_id: &str) -> bool {
+    let config = load_config();
+    if !config.tools.contains_key(tool_id) {
+        return false;
+    }
 
-    let response = client
-        .get(&url)
-        .send()
-        .map_err(|e| format!("Failed to fetch release info: {}", e))?;
+    // Also verify the app actually exists
+    tool_app_path(tool_id).is_some_and(|path| path.exists())
+}
 
-    if response.status() == 403 {
-        return Err("GitHub API rate limit exceeded. Please try again later.".to_string());
-    }
+fn get_installed_version(tool_id: &str) -> Option<String> {
+    let config = load_config();
+    config
+        .tools
+        .get(tool_id)
+        .map(|record| record.version.clone())
+}
 
-    if response.status() == 404 {
-        return Err("No releases found for this repository.".to_string());
+#[tauri::command]
+fn check_tool_status<R: Runtime>(app: tauri::AppHandle<R>, tool_id: String) -> ToolStatus {
+    let mut status = tool_status(&tool_id);
+    status.update_queued = app
+        .state::<AppState>()
+        .queued_updates
+        .lock()
+        .unwrap()
+        .tools
+        .contains(&tool_id);
+    if status.error.is_none() {
+        let latest_version = status.latest_version.as_ref().filter(|_| status.has_update);
+        record_available_update(&app, &tool_id, latest_version);
     }
+    status
+}
 
-    if !response.status().is_success() {
-        return Err(format!("GitHub API error: {}", response.status()));
+// Remember which tools have an update pending and reflect it in the tray
+fn record_available_update<R: Runtime>(
+    app: &tauri::AppHandle<R>,
+    tool_id: &str,
+    latest_version: Option<&String>,
+) {
+    {
+        let state = app.state::<AppState>();
+        let mut available_updates = state.available_updates.lock().unwrap();
+        match latest_version {
+            Some(version) => available_updates.insert(tool_id.to_string(), version.clone()),
+            None => available_updates.remove(tool_id),
+        };
     }
-
-    response
-        .json::<GitHubRelease>()
-        .map_err(|e| format!("Failed to parse release info: {}", e))
+    sync_update_indicators(app);
 }
 
-fn find_app_asset(release: &GitHubRelease) -> Option<&GitHubAsset> {
-    // Look for .app.tar.gz first (preferred), then .app.zip, then .dmg
-    release
-        .assets
-        .iter()
-        .find(|a| a.name.ends_with(".app.tar.gz"))
-        .or_else(|| release.assets.iter().find(|a| a.name.ends_with(".app.zip")))
-        .or_else(|| release.assets.iter().find(|a| a.name.ends_with(".dmg")))
+// Derive `has_updates` from pending tool and launcher updates and reflect it
+// in the tray icon and menu
+fn sync_update_indicators<R: Runtime>(app: &tauri::AppHandle<R>) {
+    let has_updates = {
+        let state = app.state::<AppState>();
+        let has_updates = !state.available_updates.lock().unwrap().is_empty()
+            || state.launcher_update.lock().unwrap().is_some();
+        *state.has_updates.lock().unwrap() = has_updates;
+        has_updates
+    };
+    apply_tray_update_icon(app, has_updates);
+    refresh_tray(app);
 }
 
-fn download_file(url: &str, dest: &PathBuf) -> Result<(), String> {
-    let client = reqwest::blocking::Client::builder()
-        .user_agent("Story-Launcher/1.0")
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
-
-    let response = client
-        .get(url)
-        .send()
-        .map_err(|e| format!("Failed to download: {}", e))?;
+// Blocking work behind an async command (downloads, hooks, waiting for a
+// tool to quit), run on a worker so it holds up neither the main thread nor
+// the async runtime
+async fn run_blocking<T>(f: impl FnOnce() -> T + Send + 'static) -> T
+where
+    T: From<LauncherError> + Send + 'static,
+{
+    tauri::async_runtime::spawn_blocking(f)
+        .await
+        .unwrap_or_else(|e| LauncherError::Io(format!("Background task failed: {}", e)).into())
+}
 
-    if !response.status().is_success() {
-        return Err(format!("Download failed: {}", response.status()));
+// Install the latest release and clear the tool's pending update
+fn install_latest<R: Runtime>(app: &tauri::AppHandle<R>, tool_id: &str) -> InstallResult {
+    let result = install(tool_id, None);
+    if result.is_ok() {
+        record_available_update(app, tool_id, None);
     }
-
-    let bytes = response
-        .bytes()
-        .map_err(|e| format!("Failed to read download: {}", e))?;
-
-    let mut file = File::create(dest).map_err(|e| format!("Failed to create file: {}", e))?;
-
-    file.write_all(&bytes)
-        .map_err(|e| format!("Failed to write file: {}", e))?;
-
-    Ok(())
+    result.into()
 }
 
-fn extract_tar_gz(archive_path: &PathBuf, dest_dir: &PathBuf) -> Result<(), String> {
-    let file = File::open(archive_path).map_err(|e| format!("Failed to open archive: {}", e))?;
-
-    let decoder = GzDecoder::new(file);
-    let mut archive = Archive::new(decoder);
-
-    archive
-        .unpack(dest_dir)
-        .map_err(|e| format!("Failed to extract archive: {}", e))?;
-
-    Ok(())
+// Async so downloads, dependency installs and post-install hooks don't block
+// the main thread
+#[tauri::command]
+async fn install_tool<R: Runtime>(app: tauri::AppHandle<R>, tool_id: String) -> InstallResult {
+    run_blocking(move || install_latest(&app, &tool_id)).await
 }
 
-fn extract_zip(archive_path: &PathBuf, dest_dir: &PathBuf) -> Result<(), String> {
-    let file = File::open(archive_path).map_err(|e| format!("Failed to open archive: {}", e))?;
-
-    let mut archive =
-        zip::ZipArchive::new(file).map_err(|e| format!("Failed to read zip: {}", e))?;
-
-    for i in 0..archive.len() {
-        let mut file = archive
-            .by_index(i)
-            .map_err(|e| format!("Failed to read zip entry: {}", e))?;
-
-        let outpath = dest_dir.join(file.name());
-
-        if file.name().ends_with('/') {
-            fs::create_dir_all(&outpath)
-                .map_err(|e| format!("Failed to create directory: {}", e))?;
-        } else {
-            if let Some(parent) = outpath.parent() {
-                fs::create_dir_all(parent)
-                    .map_err(|e| format!("Failed to create directory: {}", e))?;
-            }
-            let mut outfile =
-                File::create(&outpath).map_err(|e| format!("Failed to create file: {}", e))?;
-            io::copy(&mut file, &mut outfile)
-                .map_err(|e| format!("Failed to write file: {}", e))?;
-        }
+#[tauri::command]
+async fn update_tool<R: Runtime>(app: tauri::AppHandle<R>, tool_id: String) -> InstallResult {
+    // Update is the same as install - it will replace the existing version
+    install_tool(app, tool_id).await
+}
 
-        // Set permissions on Unix
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            if let Some(mode) = file.unix_mode() {
-                fs::set_permissions(&outpath, fs::Permissions::from_mode(mode)).ok();
-            }
+// Async so pre-uninstall hooks don't block the main thread
+#[tauri::command]
+async fn uninstall_tool<R: Runtime>(app: tauri::AppHandle<R>, tool_id: String) -> ActionResult {
+    run_blocking(move || {
+        let result = uninstall(&tool_id);
+        if result.is_ok() {
+            record_available_update(&app, &tool_id, None);
         }
-    }
-
-    Ok(())
+        result.into()
+    })
+    .await
 }
 
-fn get_app_path(app_name: &str) -> PathBuf {
-    get_apps_dir().join(app_name)
+fn uninstall(tool_id: &str) -> Result<String, LauncherError> {
+    let result = remove_tool(tool_id);
+    let fields = json!({ "tool_id": tool_id });
+    match &result {
+        Ok(_) => logging::info("uninstall", fields),
+        Err(e) => logging::error("uninstall", e, fields),
+    }
+    result
 }
 
-fn is_tool_installed(tool_id: &str) -> bool {
-    let config = load_config();
-    if !config.tools.contains_key(tool_id) {
-        return false;
+fn remove_tool(tool_id: &str) -> Result<String, LauncherError> {
+    let tool = find_tool(tool_id).ok_or_else(|| LauncherError::UnknownTool(tool_id.to_string()))?;
+    if process::is_tool_running(tool_id) {
+        return Err(LauncherError::ToolRunning(tool_id.to_string()));
+    }
+    let dependents = dependencies::dependents(tool_id);
+    if !dependents.is_empty() {
+        return Err(LauncherError::Dependency(format!(
+            "{} is needed by {}",
+            tool.name,
+            dependents.join(", ")
+        )));
     }
 
-    // Also verify the app actually exists
-    let app_path = match tool_id {
-        "resolve-sync" => get_app_path(RESOLVE_SYNC_APP_NAME),
-        _ => return false,
+    let app_path = get_app_path(tool.app_name);
+    let removed = match tool.kind {
+        // Every file the install placed in Resolve's folders
+        ArtifactKind::ResolveScript { .. } => load_config()
+            .tools
+            .get(tool_id)
+            .map(|record| record.files.clone())
+            .unwrap_or_default()
+            .iter()
+            .filter(|path| path.exists())
+            .try_for_each(fs::remove_file),
+        // External installs belong to the user; only forget about them
+        ArtifactKind::App
+            if discovery::install_source(tool_id) != Some(InstallSource::External)
+                && app_path.exists() =>
+        {
+            fs::remove_dir_all(&app_path)
+        }
+        ArtifactKind::App => Ok(()),
     };
+    removed.map_err(|e| LauncherError::from_io("Failed to remove app", e))?;
 
-    app_path.exists()
-}
+    update_config(|config| config.tools.remove(tool_id))?;
+    bundle::remove_version_marker(tool_id);
 
-fn get_installed_version(tool_id: &str) -> Option<String> {
-    let config = load_config();
-    config.tools.get(tool_id).cloned()
+    Ok("Uninstalled app".to_string())
 }
 
 #[tauri::command]
-fn check_tool_status(tool_id: String) -> ToolStatus {
-    let repo = match tool_id.as_str() {
-        "resolve-sync" => RESOLVE_SYNC_REPO,
-        _ => {
-            return ToolStatus {
-                installed: false,
-                installed_version: None,
-                latest_version: None,
-                has_update: false,
-                error: Some("Unknown tool".to_string()),
-            }
-        }
-    };
+fn launch_tool<R: Runtime>(app: tauri::AppHandle<R>, tool_id: String) -> ActionResult {
+    supervisor::launch_supervised(&app, &tool_id, &LaunchOptions::default()).into()
+}
 
-    let installed = is_tool_installed(&tool_id);
-    let installed_version = get_installed_version(&tool_id);
-
-    // Fetch latest release from GitHub
-    match get_latest_release(repo) {
-        Ok(release) => {
-            let latest_version = release.tag_name.trim_start_matches('v').to_string();
-            let has_update = installed
-                && installed_version
-                    .as_ref()
-                    .map(|v| v != &latest_version)
-                    .unwrap_or(false);
-
-            ToolStatus {
-                installed,
-                installed_version,
-                latest_version: Some(latest_version),
-                has_update,
-                error: None,
-            }
-        }
-        Err(e) => ToolStatus {
-            installed,
-            installed_version,
-            latest_version: None,
-            has_update: false,
-            error: Some(e),
-        },
+fn launch(tool_id: &str, args: &[String]) -> Result<String, LauncherError> {
+    let result = launch_app(tool_id, args);
+    let fields = json!({ "tool_id": tool_id, "args": args });
+    match &result {
+        Ok(_) => logging::info("launch", fields),
+        Err(e) => logging::error("launch", e, fields),
     }
+    result
 }
 
-#[tauri::command]
-fn install_tool(tool_id: String) -> ActionResult {
-    let (repo, app_name) = match tool_id.as_str() {
-        "resolve-sync" => (RESOLVE_SYNC_REPO, RESOLVE_SYNC_APP_NAME),
-        _ => {
-            return ActionResult {
-                success: false,
-                message: "Unknown tool".to_string(),
-            }
-        }
-    };
+fn launch_app(tool_id: &str, args: &[String]) -> Result<String, LauncherError> {
+    let tool = find_tool(tool_id).ok_or_else(|| LauncherError::UnknownTool(tool_id.to_string()))?;
+    resolve::check_launchable(tool)?;
+    let app_path =
+        tool_app_path(tool_id).ok_or_else(|| LauncherError::UnknownTool(tool_id.to_string()))?;
 
-    // Ensure directories exist
-    if let Err(e) = ensure_dirs() {
-        return ActionResult {
-            success: false,
-            message: format!("Failed to create directories: {}", e),
-        };
+    if !app_path.exists() {
+        return Err(LauncherError::NotInstalled(tool_id.to_string()));
     }
 
-    // Get latest release
-    let release = match get_latest_release(repo) {
-        Ok(r) => r,
-        Err(e) => {
-            return ActionResult {
-                success: false,
-                message: e,
-            }
-        }
-    };
-
-    // Find downloadable asset
-    let asset = match find_app_asset(&release) {
-        Some(a) => a,
-        None => {
-            return ActionResult {
-                success: false,
-                message: "No compatible download found in release".to_string(),
-            }
-        }
-    };
-
-    // Download to temp file
-    let temp_dir = std::env::temp_dir();
-    let temp_file = temp_dir.join(&asset.name);
-
-    if let Err(e) = download_file(&asset.browser_download_url, &temp_file) {
-        return ActionResult {
-            success: false,
-            message: e,
-        };
+    let mut command = Command::new("open");
+    command.arg(&app_path);
+    if !args.is_empty() {
+        command.arg("--args").args(args);
     }
 
-    // Remove existing app if present
-    let app_path = get_app_path(app_name);
-    if app_path.exists() {
-        if let Err(e) = fs::remove_dir_all(&app_path) {
-            return ActionResult {
-                success: false,
-                message: format!("Failed to remove existing app: {}", e),
-            };
-        }
-    }
+    command
+        .spawn()
+        .map_err(|e| LauncherError::Launch(format!("Failed to launch: {}", e)))?;
 
-    // Extract based on file type
-    let apps_dir = get_apps_dir();
-    let result = if asset.name.ends_with(".tar.gz") {
-        extract_tar_gz(&temp_file, &apps_dir)
-    } else if asset.name.ends_with(".zip") {
-        extract_zip(&temp_file, &apps_dir)
-    } else if asset.name.ends_with(".dmg") {
-        // For DMG, we need to mount, copy, and unmount
-        extract_from_dmg(&temp_file, &apps_dir, app_name)
-    } else {
-        Err("Unsupported archive format".to_string())
-    };
+    Ok("Launched app".to_string())
+}
 
-    // Clean up temp file
-    let _ = fs::remove_file(&temp_file);
+#[tauri::command]
+fn get_installed_tools() -> Vec<String> {
+    let config = load_config();
+    config
+        .tools
+        .keys()
+        .filter(|tool_id| is_tool_installed(tool_id))
+        .cloned()
+        .collect()
+}
 
-    if let Err(e) = result {
-        return ActionResult {
-            success: false,
-            message: e,
-        };
-    }
+#[tauri::command]
+fn get_web_apps() -> Vec<WebApp> {
+    merge_web_apps(&load_config().web_apps)
+}
 
-    // Remove quarantine attribute
-    let _ = Command::new("xattr")
-        .args(["-cr", app_path.to_str().unwrap_or("")])
-        .output();
+fn find_web_app(id: &str) -> Result<WebApp, LauncherError> {
+    get_web_apps()
+        .into_iter()
+        .find(|web_app| web_app.id == id)
+        .ok_or_else(|| LauncherError::UnknownWebApp(id.to_string()))
+}
 
-    // Update config
-    let mut config = load_config();
-    let version = release.tag_name.trim_start_matches('v').to_string();
-    config.tools.insert(tool_id.clone(), version.clone());
+// Insert or replace a user bookmark; built-in web apps are overridden by
+// storing a bookmark with the same id
+fn save_web_app(web_app: WebApp) -> Result<(), LauncherError> {
+    update_config(|config| {
+        match config
+            .web_apps
+            .iter_mut()
+            .find(|existing| existing.id == web_app.id)
+        {
+            Some(existing) => *existing = web_app,
+            None => config.web_apps.push(web_app),
+        }
+    })
+}
 
-    if let Err(e) = save_config(&config) {
-        return ActionResult {
-            success: false,
-            message: format!("Failed to save config: {}", e),
-        };
+#[tauri::command]
+fn add_web_app<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    name: String,
+    url: String,
+    icon: Option<String>,
+    browser: Option<String>,
+    profile: Option<String>,
+    open_in_window: Option<bool>,
+) -> ActionResult {
+    let id = web_app_id_from_name(&name);
+    if id.is_empty() {
+        return LauncherError::InvalidInput("Web app name is required".to_string()).into();
     }
 
-    ActionResult {
-        success: true,
-        message: format!("Installed version {}", version),
+    if !url.starts_with("https://") && !url.starts_with("http://") {
+        return LauncherError::InvalidInput(
+            "Web app URL must start with http:// or https://".to_string(),
+        )
+        .into();
     }
-}
 
-fn extract_from_dmg(dmg_path: &PathBuf, dest_dir: &PathBuf, app_name: &str) -> Result<(), String> {
-    // Mount DMG
-    let output = Command::new("hdiutil")
-        .args(["attach", dmg_path.to_str().unwrap(), "-nobrowse", "-quiet"])
-        .output()
-        .map_err(|e| format!("Failed to mount DMG: {}", e))?;
+    let result = save_web_app(WebApp {
+        id: id.clone(),
+        name,
+        url,
+        icon,
+        browser,
+        profile,
+        open_in_window: open_in_window.unwrap_or(false),
+        builtin: false,
+    });
+    refresh_tray(&app);
+
+    result.map(|_| format!("Added web app {}", id)).into()
+}
 
-    if !output.status.success() {
-        return Err("Failed to mount DMG".to_string());
-    }
+#[tauri::command]
+fn remove_web_app<R: Runtime>(app: tauri::AppHandle<R>, id: String) -> ActionResult {
+    let result = update_config(|config| {
+        let count = config.web_apps.len();
+        config.web_apps.retain(|web_app| web_app.id != id);
+        config.web_apps.len() != count
+    });
+
+    let result = match result {
+        Ok(true) => Ok(format!("Removed web app {}", id)),
+        Ok(false) => Err(LauncherError::InvalidInput(
+            "Only user-added web apps can be removed".to_string(),
+        )),
+        Err(e) => Err(e),
+    };
+    refresh_tray(&app);
 
-    // Find mount point
-    let output = Command::new("hdiutil")
-        .args(["info", "-plist"])
-        .output()
-        .map_err(|e| format!("Failed to get mount info: {}", e))?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let mount_point = stdout
-        .lines()
-        .skip_while(|l| !l.contains("/Volumes/"))
-        .next()
-        .and_then(|l| l.split("<string>").nth(1))
-        .and_then(|l| l.split("</string>").next())
-        .ok_or("Failed to find mount point")?
-        .to_string();
-
-    // Copy app
-    let src = PathBuf::from(&mount_point).join(app_name);
-    let dest = dest_dir.join(app_name);
-
-    let copy_result = Command::new("cp")
-        .args(["-R", src.to_str().unwrap(), dest.to_str().unwrap()])
-        .output();
-
-    // Unmount DMG
-    let _ = Command::new("hdiutil")
-        .args(["detach", &mount_point, "-quiet"])
-        .output();
-
-    copy_result
-        .map_err(|e| format!("Failed to copy app: {}", e))
-        .and_then(|o| {
-            if o.status.success() {
-                Ok(())
-            } else {
-                Err("Failed to copy app from DMG".to_string())
-            }
-        })
+    result.into()
 }
 
 #[tauri::command]
-fn update_tool(tool_id: String) -> ActionResult {
-    // Update is the same as install - it will replace the existing version
-    install_tool(tool_id)
+fn set_web_app_open_in_window<R: Runtime>(
+    app: tauri::AppHandle<R>,
+    id: String,
+    open_in_window: bool,
+) -> ActionResult {
+    let result = find_web_app(&id).and_then(|mut web_app| {
+        web_app.open_in_window = open_in_window;
+        save_web_app(web_app)
+    });
+    refresh_tray(&app);
+
+    result.map(|_| "Updated web app".to_string()).into()
 }
 
+// Async so window creation doesn't block the main thread it has to run on
 #[tauri::command]
-fn launch_tool(tool_id: String) -> ActionResult {
-    let app_name = match tool_id.as_str() {
-        "resolve-sync" => RESOLVE_SYNC_APP_NAME,
-        _ => {
-            return ActionResult {
-                success: false,
-                message: "Unknown tool".to_string(),
-            }
-        }
-    };
+async fn open_web_app<R: Runtime>(app: tauri::AppHandle<R>, id: String) -> ActionResult {
+    open_web_app_by_id(&app, &id).into()
+}
 
-    let app_path = get_app_path(app_name);
+fn open_web_app_by_id<R: Runtime>(
+    app: &tauri::AppHandle<R>,
+    id: &str,
+) -> Result<String, LauncherError> {
+    let web_app = find_web_app(id)?;
 
-    if !app_path.exists() {
-        return ActionResult {
-            success: false,
-            message: "App not installed".to_string(),
-        };
+    if web_app.open_in_window {
+        open_web_app_window(app, &web_app)?;
+    } else {
+        open_url_in_browser(&web_app)?;
     }
 
-    match Command::new("open").arg(&app_path).spawn() {
-        Ok(_) => ActionResult {
-            success: true,
-            message: "Launched app".to_string(),
-        },
-        Err(e) => ActionResult {
-            success: false,
-            message: format!("Failed to launch: {}", e),
-        },
-    }
+    Ok(format!("Opened {}", web_app.name))
 }
 
-#[tauri::command]
-fn get_installed_tools() -> Vec<String> {
-    let config = load_config();
-    config
-        .tools
-        .keys()
-        .filter(|tool_id| is_tool_installed(tool_id))
-        .cloned()
-        .collect()
+fn open_url_in_browser(web_app: &WebApp) -> Result<(), LauncherError> {
+    // Profiles need a browser flag, which the opener plugin can't pass through
+    if let (Some(browser), Some(profile)) = (&web_app.browser, &web_app.profile) {
+        let profile_arg = format!("--profile-directory={}", profile);
+        let mut command = if cfg!(target_os = "macos") {
+            let mut command = Command::new("open");
+            command.args(["-na", browser, "--args", &profile_arg]);
+            command
+        } else {
+            let mut command = Command::new(browser);
+            command.arg(&profile_arg);
+            command
+        };
+
+        return command
+            .arg(&web_app.url)
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| LauncherError::Launch(format!("Failed to open {}: {}", browser, e)));
+    }
+
+    tauri_plugin_opener::open_url(&web_app.url, web_app.browser.as_deref())
+        .map_err(|e| LauncherError::Launch(format!("Failed to open {}: {}", web_app.url, e)))
 }
 
 #[tauri::command]
 fn set_tray_update_icon<R: Runtime>(app: tauri::AppHandle<R>, has_update: bool) {
-    if let Some(tray) = app.tray_by_id("main-tray") {
+    // The window only knows about the tools it shows; keep the badge for
+    // anything else the background checks found
+    let has_updates = *app.state::<AppState>().has_updates.lock().unwrap();
+    apply_tray_update_icon(&app, has_update || has_updates);
+}
+
+fn apply_tray_update_icon<R: Runtime>(app: &tauri::AppHandle<R>, has_update: bool) {
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
         let icon_path = if has_update {
             include_bytes!("../icons/tray-icon-update.png").to_vec()
         } else {
@@ -512,71 +574,20 @@ fn set_tray_update_icon<R: Runtime>(app: tauri::AppHandle<R>, has_update: bool)
     }
 }
 
-fn create_tray_menu<R: Runtime>(
-    app: &tauri::AppHandle<R>,
-    installed_tools: &[String],
-) -> tauri::Result<Menu<R>> {
-    let mut items: Vec<Box<dyn tauri::menu::IsMenuItem<R>>> = Vec::new();
-
-    // Add installed tools
-    if installed_tools.contains(&"resolve-sync".to_string()) {
-        items.push(Box::new(MenuItem::with_id(
-            app,
-            "resolve-sync",
-            "Resolve Sync Script",
-            true,
-            None::<&str>,
-        )?));
-    }
-
-    // Always show web apps
-    items.push(Box::new(MenuItem::with_id(
-        app,
-        "spellbook",
-        "Spellbook",
-        true,
-        None::<&str>,
-    )?));
-    items.push(Box::new(MenuItem::with_id(
-        app,
-        "portal",
-        "Story Portal",
-        true,
-        None::<&str>,
-    )?));
-
-    items.push(Box::new(PredefinedMenuItem::separator(app)?));
-    items.push(Box::new(MenuItem::with_id(
-        app,
-        "check-updates",
-        "Check for Updates",
-        true,
-        None::<&str>,
-    )?));
-    items.push(Box::new(MenuItem::with_id(
-        app,
-        "open-launcher",
-        "Open Story Launcher",
-        true,
-        None::<&str>,
-    )?));
-    items.push(Box::new(PredefinedMenuItem::separator(app)?));
-    items.push(Box::new(MenuItem::with_id(
-        app,
-        "quit",
-        "Quit Story Launcher",
-        true,
-        None::<&str>,
-    )?));
-
-    // Build menu from refs
-    let item_refs: Vec<&dyn tauri::menu::IsMenuItem<R>> = items.iter().map(|b| b.as_ref()).collect();
-    Menu::with_items(app, &item_refs)
-}
-
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tauri::Builder::default()
+    let mut builder = tauri::Builder::default();
+
+    // A second launch (e.g. from a deep link) forwards its URL to the running instance
+    #[cfg(desktop)]
+    {
+        builder = builder.plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
+            show_main_window(app)
+        }));
+    }
+
+    builder
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_store::Builder::new().build())
         .plugin(tauri_plugin_autostart::init(
@@ -587,53 +598,30 @@ pub fn run() {
         .plugin(tauri_plugin_process::init())
         .manage(AppState {
             has_updates: Mutex::new(false),
+            available_updates: Mutex::new(HashMap::new()),
+            pending_install: Mutex::new(None),
+            launcher_update: Mutex::new(None),
+            scheduler: Mutex::new(None),
+            queued_updates: Mutex::new(UpdateQueue::default()),
+            supervised: Mutex::new(HashMap::new()),
         })
         .setup(|app| {
             let handle = app.handle().clone();
 
+            config::upgrade_config();
+
             // Get installed tools for tray menu
             let installed_tools = get_installed_tools();
 
             // Create tray icon
             let tray_icon = Image::from_bytes(include_bytes!("../icons/tray-icon.png"))?;
-            let menu = create_tray_menu(&handle, &installed_tools)?;
+            let menu = create_tray_menu(&handle, &installed_tools, &HashMap::new())?;
 
-            let _tray = TrayIconBuilder::with_id("main-tray")
+            let _tray = TrayIconBuilder::with_id(TRAY_ID)
                 .icon(tray_icon)
                 .menu(&menu)
                 .tooltip("Story Launcher")
-                .on_menu_event(move |app, event| match event.id.as_ref() {
-                    "resolve-sync" => {
-                        let _ = launch_tool("resolve-sync".to_string());
-                    }
-                    "spellbook" => {
-                        let _ = Command::new("open")
-                            .arg("https://spellbook.story.inc")
-                            .spawn();
-                    }
-                    "portal" => {
-                        let _ = Command::new("open")
-                            .arg("https://portal.story.inc")
-                            .spawn();
-                    }
-                    "check-updates" => {
-                        if let Some(window) = app.get_webview_window("main") {
-                            let _ = window.show();
-                            let _ = window.set_focus();
-                            let _ = window.emit("check-updates", ());
-                        }
-                    }
-                    "open-launcher" => {
-                        if let Some(window) = app.get_webview_window("main") {
-                            let _ = window.show();
-                            let _ = window.set_focus();
-                        }
-                    }
-                    "quit" => {
-                        app.exit(0);
-                    }
-                    _ => {}
-                })
+                .on_menu_event(|app, event| tray::handle_menu_event(app, event.id.as_ref()))
                 .on_tray_icon_event(|tray, event| {
                     if let TrayIconEvent::Click {
                         button: MouseButton::Left,
@@ -641,15 +629,31 @@ pub fn run() {
                         ..
                     } = event
                     {
-                        let app = tray.app_handle();
-                        if let Some(window) = app.get_webview_window("main") {
-                            let _ = window.show();
-                            let _ = window.set_focus();
-                        }
+                        show_main_window(tray.app_handle());
                     }
                 })
                 .build(app)?;
 
+            // Route story-launcher:// URLs, including the one we were launched with
+            #[cfg(any(windows, target_os = "linux"))]
+            let _ = app.deep_link().register_all();
+
+            let deep_link_handle = handle.clone();
+            app.deep_link().on_open_url(move |event| {
+                for url in event.urls() {
+                    handle_deep_link(&deep_link_handle, &url);
+                }
+            });
+            if let Ok(Some(urls)) = app.deep_link().get_current() {
+                for url in urls {
+                    handle_deep_link(&handle, &url);
+                }
+            }
+
+            scheduler::start(handle.clone());
+            policy::start(handle.clone());
+            startup::start(handle.clone());
+
             // Handle window close - hide instead of quit
             if let Some(window) = app.get_webview_window("main") {
                 let window_clone = window.clone();
@@ -667,8 +671,43 @@ pub fn run() {
             check_tool_status,
             install_tool,
             update_tool,
+            uninstall_tool,
             launch_tool,
             get_installed_tools,
+            get_web_apps,
+            add_web_app,
+            remove_web_app,
+            set_web_app_open_in_window,
+            open_web_app,
+            logging::get_recent_logs,
+            diagnostics::run_diagnostics,
+            logging::export_diagnostics,
+            deep_link::get_pending_deep_link_install,
+            deep_link::confirm_deep_link_install,
+            discovery::adopt_tool,
+            location::get_install_location,
+            location::set_install_location,
+            scheduler::check_for_updates_now,
+            scheduler::get_update_check_settings,
+            scheduler::set_update_check_settings,
+            process::queue_update,
+            process::restart_and_update,
+            supervisor::running_tools,
+            supervisor::quit_tool,
+            supervisor::get_tool_settings,
+            supervisor::set_auto_restart,
+            tool_logs::tail_tool_log,
+            profiles::get_launch_profiles,
+            profiles::launch_tool_profile,
+            profiles::save_launch_profile,
+            profiles::delete_launch_profile,
+            startup::set_startup_settings,
+            dependencies::get_install_plan,
+            policy::get_update_policies,
+            policy::set_update_policy,
+            policy::defer_update,
+            recovery::get_config_recovery,
+            recovery::dismiss_config_recovery,
             set_tray_update_icon
         ])
         .run(tauri::generate_context!())
@@ -0,0 +1,130 @@
+// Where tools get installed. By default everything lives under ~/.story-tools;
+// shared edit bays can point the install root at a common volume or /opt and
+// switch to system-wide mode, where install records move into a manifest
+// shared by every user and config.json keeps only per-user preferences.
+
+use std::env;
+use std::path::PathBuf;
+
+use serde::Serialize;
+use serde_json::json;
+use tauri::{AppHandle, Runtime};
+
+use crate::config::{load_user_config, ToolsConfig};
+use crate::error::LauncherError;
+use crate::logging;
+use crate::tray::refresh_tray;
+use crate::{get_tools_dir, update_config, ActionResult};
+
+pub const INSTALL_ROOT_ENV: &str = "STORY_LAUNCHER_INSTALL_ROOT";
+pub const SYSTEM_WIDE_ENV: &str = "STORY_LAUNCHER_SYSTEM_WIDE";
+
+const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RootSource {
+    Env,
+    Config,
+    Default,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct InstallLocation {
+    pub root: PathBuf,
+    pub system_wide: bool,
+    pub source: RootSource, // what decided `root`
+}
+
+impl InstallLocation {
+    pub fn apps_dir(&self) -> PathBuf {
+        self.root.join("apps")
+    }
+
+    // Shared install records, only used in system-wide mode
+    pub fn manifest_path(&self) -> Option<PathBuf> {
+        self.system_wide.then(|| self.root.join(MANIFEST_FILE_NAME))
+    }
+}
+
+pub fn install_location() -> InstallLocation {
+    install_location_for(&load_user_config())
+}
+
+// The environment wins over config.json so admins can pin a machine's root
+pub fn install_location_for(config: &ToolsConfig) -> InstallLocation {
+    let system_wide = match env::var(SYSTEM_WIDE_ENV) {
+        Ok(value) => matches!(value.as_str(), "1" | "true" | "yes"),
+        Err(_) => config.system_wide,
+    };
+
+    let (root, source) = match (env::var_os(INSTALL_ROOT_ENV), &config.install_root) {
+        (Some(root), _) if !root.is_empty() => (PathBuf::from(root), RootSource::Env),
+        (_, Some(root)) => (root.clone(), RootSource::Config),
+        _ if system_wide => (default_system_root(), RootSource::Default),
+        _ => (get_tools_dir(), RootSource::Default),
+    };
+
+    InstallLocation {
+        root,
+        system_wide,
+        source,
+    }
+}
+
+fn default_system_root() -> PathBuf {
+    if cfg!(target_os = "macos") {
+        PathBuf::from("/Users/Shared/Story Tools")
+    } else if cfg!(windows) {
+        PathBuf::from(env::var_os("ProgramData").unwrap_or_else(|| "C:\\ProgramData".into()))
+            .join("Story Tools")
+    } else {
+        PathBuf::from("/opt/story-tools")
+    }
+}
+
+#[tauri::command]
+pub fn get_install_location() -> InstallLocation {
+    install_location()
+}
+
+// Existing installs are not moved; tools under the old root show as not
+// installed until they are reinstalled or adopted from there
+#[tauri::command]
+pub fn set_install_location<R: Runtime>(
+    app: AppHandle<R>,
+    root: Option<String>,
+    system_wide: bool,
+) -> ActionResult {
+    let root = root
+        .filter(|root| !root.trim().is_empty())
+        .map(PathBuf::from);
+    if root.as_ref().is_some_and(|root| !root.is_absolute()) {
+        return LauncherError::InvalidInput("Install root must be an absolute path".to_string())
+            .into();
+    }
+
+    let result = update_config(|config| {
+        config.install_root = root.clone();
+        config.system_wide = system_wide;
+        install_location_for(config)
+    });
+    refresh_tray(&app);
+
+    let fields = json!({ "root": root, "system_wide": system_wide });
+    match &result {
+        Ok(_) => logging::info("install_location_changed", fields),
+        Err(e) => logging::error("install_location_changed", e, fields),
+    }
+
+    result
+        .map(|location| match location.source {
+            RootSource::Env => format!(
+                "Saved, but {} is set and takes precedence ({})",
+                INSTALL_ROOT_ENV,
+                location.root.display()
+            ),
+            _ => format!("Tools will be installed in {}", location.root.display()),
+        })
+        .into()
+}
@@ -0,0 +1,200 @@
+// Structured JSON-lines log of launcher operations under ~/.story-tools/logs.
+// Each line is `{ "ts": <unix ms>, "level": .., "event": .., ...fields }`.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Map, Value};
+use tauri::{AppHandle, Runtime};
+use zip::write::SimpleFileOptions;
+
+use crate::error::LauncherError;
+use crate::location::install_location;
+use crate::{get_config_path, get_tools_dir, now_secs, ActionResult};
+
+const LOG_FILE_NAME: &str = "launcher.jsonl";
+const MAX_LOG_SIZE: u64 = 1024 * 1024;
+const MAX_ROTATED_LOGS: usize = 5;
+
+// Serializes writes and rotation across threads
+static LOG_LOCK: Mutex<()> = Mutex::new(());
+
+pub fn get_logs_dir() -> PathBuf {
+    get_tools_dir().join("logs")
+}
+
+fn log_path(index: usize) -> PathBuf {
+    match index {
+        0 => get_logs_dir().join(LOG_FILE_NAME),
+        n => get_logs_dir().join(format!("launcher.{}.jsonl", n)),
+    }
+}
+
+pub fn info(event: &str, fields: Value) {
+    write_entry("info", event, fields);
+}
+
+pub fn error(event: &str, error: &LauncherError, fields: Value) {
+    let mut fields = match fields {
+        Value::Object(map) => map,
+        _ => Map::new(),
+    };
+    fields.insert("error".to_string(), json!(error));
+    write_entry("error", event, Value::Object(fields));
+}
+
+// Logging must never break the operation being logged, so failures are dropped
+fn write_entry(level: &str, event: &str, fields: Value) {
+    let ts = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default();
+
+    let mut entry = Map::new();
+    entry.insert("ts".to_string(), json!(ts));
+    entry.insert("level".to_string(), json!(level));
+    entry.insert("event".to_string(), json!(event));
+    if let Value::Object(fields) = fields {
+        entry.extend(fields);
+    }
+
+    let Ok(line) = serde_json::to_string(&Value::Object(entry)) else {
+        return;
+    };
+
+    let _guard = LOG_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let _ = append_line(&line);
+}
+
+fn append_line(line: &str) -> io::Result<()> {
+    fs::create_dir_all(get_logs_dir())?;
+
+    let path = log_path(0);
+    if fs::metadata(&path).map(|m| m.len()).unwrap_or(0) >= MAX_LOG_SIZE {
+        rotate()?;
+    }
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)
+}
+
+// launcher.jsonl -> launcher.1.jsonl -> ... -> launcher.5.jsonl (dropped)
+fn rotate() -> io::Result<()> {
+    rotate_files(log_path, MAX_ROTATED_LOGS)
+}
+
+// Shift path(0..keep) up by one, dropping path(keep)
+pub fn rotate_files(path: impl Fn(usize) -> PathBuf, keep: usize) -> io::Result<()> {
+    let _ = fs::remove_file(path(keep));
+    for index in (0..keep).rev() {
+        let from = path(index);
+        if from.exists() {
+            fs::rename(from, path(index + 1))?;
+        }
+    }
+    Ok(())
+}
+
+fn read_entries(path: &Path) -> Vec<Value> {
+    let Ok(file) = File::open(path) else {
+        return Vec::new();
+    };
+
+    BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str(&line).ok())
+        .collect()
+}
+
+// Most recent entries across the current and rotated files, oldest first
+#[tauri::command]
+pub fn get_recent_logs(limit: Option<usize>) -> Vec<Value> {
+    let limit = limit.unwrap_or(200);
+    let mut entries = Vec::new();
+
+    for index in 0..=MAX_ROTATED_LOGS {
+        let mut older = read_entries(&log_path(index));
+        older.append(&mut entries);
+        entries = older;
+        if entries.len() >= limit {
+            break;
+        }
+    }
+
+    let skip = entries.len().saturating_sub(limit);
+    entries.split_off(skip)
+}
+
+// Bundle logs and config into a zip that can be attached to a support ticket
+#[tauri::command]
+pub fn export_diagnostics<R: Runtime>(app: AppHandle<R>) -> ActionResult {
+    let version = app.package_info().version.to_string();
+    export_diagnostics_zip(&version)
+        .map(|path| path.display().to_string())
+        .into()
+}
+
+fn export_diagnostics_zip(launcher_version: &str) -> Result<PathBuf, LauncherError> {
+    let ts = now_secs();
+    let dest_dir = dirs::desktop_dir().unwrap_or_else(get_tools_dir);
+    let dest = dest_dir.join(format!("story-launcher-diagnostics-{}.zip", ts));
+
+    let file = File::create(&dest)
+        .map_err(|e| LauncherError::from_io("Failed to create diagnostics file", e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = SimpleFileOptions::default();
+    let zip_err =
+        |e: zip::result::ZipError| LauncherError::Io(format!("Failed to write diagnostics: {}", e));
+
+    let summary = json!({
+        "launcher_version": launcher_version,
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "tools_dir": get_tools_dir(),
+        "install_location": install_location(),
+        "exported_at": ts,
+    });
+    zip.start_file("summary.json", options).map_err(zip_err)?;
+    zip.write_all(
+        serde_json::to_string_pretty(&summary)
+            .unwrap_or_default()
+            .as_bytes(),
+    )
+    .map_err(|e| LauncherError::from_io("Failed to write diagnostics", e))?;
+
+    let mut files = vec![(get_config_path(), "config.json".to_string())];
+    if let Ok(entries) = fs::read_dir(get_logs_dir()) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = format!("logs/{}", entry.file_name().to_string_lossy());
+            if path.is_file() {
+                files.push((path, name));
+            } else if let Ok(tool_entries) = fs::read_dir(&path) {
+                // Captured tool output, one folder per tool
+                for tool_entry in tool_entries.flatten() {
+                    let tool_name =
+                        format!("{}/{}", name, tool_entry.file_name().to_string_lossy());
+                    files.push((tool_entry.path(), tool_name));
+                }
+            }
+        }
+    }
+
+    for (path, name) in files {
+        let Ok(contents) = fs::read(&path) else {
+            continue;
+        };
+        zip.start_file(name, options).map_err(zip_err)?;
+        zip.write_all(&contents)
+            .map_err(|e| LauncherError::from_io("Failed to write diagnostics", e))?;
+    }
+
+    zip.finish().map_err(zip_err)?;
+    info("diagnostics_exported", json!({ "path": dest }));
+
+    Ok(dest)
+}
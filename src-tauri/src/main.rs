@@ -2,5 +2,11 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    // `story-launcher status`, `install <tool>`, ... run headless and exit
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = story_launcher_lib::cli::run(&args) {
+        std::process::exit(code);
+    }
+
     story_launcher_lib::run()
 }
@@ -0,0 +1,243 @@
+// Decides what happens when a tool update is available. Runs in the backend
+// on a timer and after every update check, so it works with the window closed.
+
+use std::collections::HashMap;
+use std::thread;
+use std::time::Duration;
+
+use chrono::{Local, NaiveTime};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use crate::discovery::{install_source, InstallSource};
+use crate::error::LauncherError;
+use crate::logging;
+use crate::process::is_tool_running;
+use crate::tools::find_tool;
+use crate::{install_latest, load_config, now_secs, update_config, ActionResult, AppState};
+
+const EVALUATION_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateMode {
+    Manual, // never checked in the background
+    Notify, // checked and flagged in the tray, installed by the user
+    #[default]
+    AutoWhenNotRunning, // installed as soon as the tool isn't running
+    AutoScheduled, // installed inside the maintenance window, when the tool isn't running
+}
+
+// Local wall-clock times as "HH:MM"; a window may wrap past midnight
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MaintenanceWindow {
+    pub start: String,
+    pub end: String,
+}
+
+impl Default for MaintenanceWindow {
+    fn default() -> Self {
+        MaintenanceWindow {
+            start: "02:00".to_string(),
+            end: "05:00".to_string(),
+        }
+    }
+}
+
+impl MaintenanceWindow {
+    fn parse(time: &str) -> Result<NaiveTime, LauncherError> {
+        NaiveTime::parse_from_str(time, "%H:%M").map_err(|_| {
+            LauncherError::InvalidInput(format!("Invalid time {:?}, expected HH:MM", time))
+        })
+    }
+
+    fn validate(&self) -> Result<(), LauncherError> {
+        Self::parse(&self.start)?;
+        Self::parse(&self.end)?;
+        Ok(())
+    }
+
+    fn contains(&self, time: NaiveTime) -> bool {
+        let (Ok(start), Ok(end)) = (Self::parse(&self.start), Self::parse(&self.end)) else {
+            return false;
+        };
+        if start <= end {
+            start <= time && time < end
+        } else {
+            time >= start || time < end
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct UpdatePolicy {
+    #[serde(default)]
+    pub mode: UpdateMode,
+    #[serde(default)]
+    pub maintenance_window: MaintenanceWindow, // only used by AutoScheduled
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct UpdatePolicySettings {
+    #[serde(default)]
+    pub default: UpdatePolicy,
+    #[serde(default)]
+    pub tools: HashMap<String, UpdatePolicy>, // per-tool overrides of `default`
+    #[serde(default)]
+    pub deferrals: HashMap<String, u64>, // tool_id -> unix seconds until which auto-update waits
+}
+
+impl UpdatePolicySettings {
+    pub fn for_tool(&self, tool_id: &str) -> &UpdatePolicy {
+        self.tools.get(tool_id).unwrap_or(&self.default)
+    }
+}
+
+pub fn start<R: Runtime>(app: AppHandle<R>) {
+    thread::spawn(move || loop {
+        thread::sleep(EVALUATION_INTERVAL);
+        evaluate(&app);
+    });
+}
+
+// Install every pending update whose policy says now is the time
+pub fn evaluate<R: Runtime>(app: &AppHandle<R>) {
+    let pending: Vec<String> = app
+        .state::<AppState>()
+        .available_updates
+        .lock()
+        .unwrap()
+        .keys()
+        .cloned()
+        .collect();
+    if pending.is_empty() {
+        return;
+    }
+
+    let settings = load_config().update_policy;
+    let now = Local::now().time();
+    let mut installed_any = false;
+
+    for tool_id in pending {
+        if settings
+            .deferrals
+            .get(&tool_id)
+            .is_some_and(|until| *until > now_secs())
+        {
+            continue;
+        }
+
+        let policy = settings.for_tool(&tool_id);
+        let due = match policy.mode {
+            UpdateMode::Manual | UpdateMode::Notify => false,
+            UpdateMode::AutoWhenNotRunning => true,
+            UpdateMode::AutoScheduled => policy.maintenance_window.contains(now),
+        };
+        // Adopted installs only update once they're moved into the launcher
+        let external = install_source(&tool_id) == Some(InstallSource::External);
+        if !due || external || is_tool_running(&tool_id) {
+            continue;
+        }
+
+        // Claim the update first so an overlapping evaluation skips it
+        let state = app.state::<AppState>();
+        let Some(version) = state.available_updates.lock().unwrap().remove(&tool_id) else {
+            continue;
+        };
+        let result = install_latest(app, &tool_id);
+        if !result.success {
+            state
+                .available_updates
+                .lock()
+                .unwrap()
+                .insert(tool_id.clone(), version);
+        }
+        let fields = json!({ "tool_id": tool_id, "mode": policy.mode });
+        match &result.error {
+            None => logging::info("auto_update", fields),
+            Some(e) => logging::error("auto_update", e, fields),
+        }
+        installed_any |= result.success;
+    }
+
+    if installed_any {
+        let _ = app.emit("tools-changed", ());
+    }
+}
+
+#[tauri::command]
+pub fn get_update_policies() -> UpdatePolicySettings {
+    load_config().update_policy
+}
+
+// `tool_id: None` sets the default; `policy: None` removes a tool's override
+#[tauri::command]
+pub fn set_update_policy<R: Runtime>(
+    app: AppHandle<R>,
+    tool_id: Option<String>,
+    policy: Option<UpdatePolicy>,
+) -> ActionResult {
+    if let Some(policy) = &policy {
+        if let Err(e) = policy.maintenance_window.validate() {
+            return e.into();
+        }
+    }
+    if let Some(tool_id) = &tool_id {
+        if find_tool(tool_id).is_none() {
+            return LauncherError::UnknownTool(tool_id.clone()).into();
+        }
+    }
+
+    let result = update_config(|config| {
+        let settings = &mut config.update_policy;
+        match (tool_id, policy) {
+            (None, Some(policy)) => settings.default = policy,
+            (None, None) => settings.default = UpdatePolicy::default(),
+            (Some(tool_id), Some(policy)) => {
+                settings.tools.insert(tool_id, policy);
+            }
+            (Some(tool_id), None) => {
+                settings.tools.remove(&tool_id);
+            }
+        }
+    });
+
+    // A newly permissive policy may apply to an update that's already pending
+    if result.is_ok() {
+        let app = app.clone();
+        thread::spawn(move || evaluate(&app));
+    }
+    result.map(|_| "Saved update policy".to_string()).into()
+}
+
+// Postpone automatic installation of a tool's pending update
+#[tauri::command]
+pub fn defer_update(tool_id: String, hours: u64) -> ActionResult {
+    if find_tool(&tool_id).is_none() {
+        return LauncherError::UnknownTool(tool_id).into();
+    }
+
+    let until = now_secs().saturating_add(hours.saturating_mul(60 * 60));
+    let result = update_config(|config| {
+        if hours == 0 {
+            config.update_policy.deferrals.remove(&tool_id);
+        } else {
+            config
+                .update_policy
+                .deferrals
+                .insert(tool_id.clone(), until);
+        }
+    });
+    logging::info(
+        "update_deferred",
+        json!({ "tool_id": tool_id, "until": until }),
+    );
+
+    result
+        .map(|_| match hours {
+            0 => "Deferral cleared".to_string(),
+            _ => format!("Update deferred for {} hours", hours),
+        })
+        .into()
+}
@@ -0,0 +1,228 @@
+// Detects whether a tool is currently running, so updates don't replace an
+// app out from under the user, and holds updates back until it exits
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde_json::json;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use crate::error::LauncherError;
+use crate::logging;
+use crate::supervisor::{self, LaunchOptions};
+use crate::tools::find_tool;
+use crate::{install_latest, run_blocking, tool_app_path, AppState, InstallResult};
+
+const QUEUE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+const QUIT_TIMEOUT: Duration = Duration::from_secs(30);
+
+// Updates waiting for their tool to exit. `watching` is only changed under the
+// same lock, so there's never more than one watcher.
+#[derive(Default)]
+pub struct UpdateQueue {
+    pub tools: HashSet<String>,
+    pub watching: bool,
+}
+
+pub fn is_tool_running(tool_id: &str) -> bool {
+    match tool_app_path(tool_id) {
+        Some(path) => is_path_running(&path),
+        None => false,
+    }
+}
+
+// Any process whose executable lives inside the app path counts, which covers
+// both the bundle's main binary and helpers it spawns
+#[cfg(unix)]
+pub fn is_path_running(app_path: &Path) -> bool {
+    let Ok(output) = Command::new("ps").args(["-axo", "command="]).output() else {
+        return false;
+    };
+
+    let app_path = app_path.to_string_lossy();
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .any(|command| command.trim_start().starts_with(app_path.as_ref()))
+}
+
+#[cfg(windows)]
+pub fn is_path_running(app_path: &Path) -> bool {
+    let Some(name) = app_path.file_name() else {
+        return false;
+    };
+    let Ok(output) = Command::new("tasklist")
+        .args(["/FI", &format!("IMAGENAME eq {}", name.to_string_lossy())])
+        .output()
+    else {
+        return false;
+    };
+
+    String::from_utf8_lossy(&output.stdout).contains(name.to_string_lossy().as_ref())
+}
+
+// Ask an untracked tool to quit the way the user would, so it can finish and save
+pub fn quit(tool_id: &str) -> std::io::Result<()> {
+    match tool_app_path(tool_id) {
+        Some(path) => request_quit(&path),
+        None => Ok(()),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn request_quit(app_path: &Path) -> std::io::Result<()> {
+    Command::new("osascript")
+        .arg("-e")
+        .arg(format!(
+            "tell application \"{}\" to quit",
+            app_path.to_string_lossy()
+        ))
+        .output()
+        .map(|_| ())
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn request_quit(app_path: &Path) -> std::io::Result<()> {
+    Command::new("pkill")
+        .args(["-TERM", "-f"])
+        .arg(app_path)
+        .output()
+        .map(|_| ())
+}
+
+#[cfg(windows)]
+fn request_quit(app_path: &Path) -> std::io::Result<()> {
+    let name = app_path.file_name().unwrap_or_default();
+    Command::new("taskkill")
+        .arg("/IM")
+        .arg(name)
+        .output()
+        .map(|_| ())
+}
+
+fn wait_for_exit(tool_id: &str, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    while is_tool_running(tool_id) {
+        if Instant::now() >= deadline {
+            return false;
+        }
+        thread::sleep(Duration::from_millis(500));
+    }
+    true
+}
+
+// Async so installing a tool that isn't running doesn't block the main thread
+#[tauri::command]
+pub async fn queue_update<R: Runtime>(app: AppHandle<R>, tool_id: String) -> InstallResult {
+    run_blocking(move || request_update(&app, &tool_id)).await
+}
+
+// Install the tool's update now, or as soon as it exits if it's running
+pub fn request_update<R: Runtime>(app: &AppHandle<R>, tool_id: &str) -> InstallResult {
+    let Some(tool) = find_tool(tool_id) else {
+        return LauncherError::UnknownTool(tool_id.to_string()).into();
+    };
+    if !is_tool_running(tool_id) {
+        return install_latest(app, tool_id);
+    }
+
+    let start_watcher = {
+        let state = app.state::<AppState>();
+        let mut queue = state.queued_updates.lock().unwrap();
+        queue.tools.insert(tool_id.to_string());
+        !std::mem::replace(&mut queue.watching, true)
+    };
+    logging::info("update_queued", json!({ "tool_id": tool_id }));
+    if start_watcher {
+        let app = app.clone();
+        thread::spawn(move || watch_queue(&app));
+    }
+
+    InstallResult {
+        success: true,
+        message: format!("{} will update when it quits", tool.name),
+        dependencies: Vec::new(),
+        error: None,
+    }
+}
+
+// Polls until every queued tool has exited and been updated
+fn watch_queue<R: Runtime>(app: &AppHandle<R>) {
+    loop {
+        thread::sleep(QUEUE_POLL_INTERVAL);
+
+        let ready: Vec<String> = {
+            let state = app.state::<AppState>();
+            let mut queue = state.queued_updates.lock().unwrap();
+            let ready: Vec<String> = queue
+                .tools
+                .iter()
+                .filter(|tool_id| !is_tool_running(tool_id))
+                .cloned()
+                .collect();
+            for tool_id in &ready {
+                queue.tools.remove(tool_id);
+            }
+            ready
+        };
+
+        for tool_id in &ready {
+            let result = install_latest(app, tool_id);
+            let fields = json!({ "tool_id": tool_id });
+            match &result.error {
+                None => logging::info("queued_update", fields),
+                Some(e) => logging::error("queued_update", e, fields),
+            }
+        }
+        if !ready.is_empty() {
+            let _ = app.emit("tools-changed", ());
+        }
+
+        // Cleared under the queue lock, so a tool queued after this starts a new watcher
+        let state = app.state::<AppState>();
+        let mut queue = state.queued_updates.lock().unwrap();
+        if queue.tools.is_empty() {
+            queue.watching = false;
+            return;
+        }
+    }
+}
+
+// Quit the running tool, install the update, then start it again. Async so
+// waiting for the tool to quit and the install don't block the main thread.
+#[tauri::command]
+pub async fn restart_and_update<R: Runtime>(app: AppHandle<R>, tool_id: String) -> InstallResult {
+    run_blocking(move || restart_and_install(&app, tool_id)).await
+}
+
+fn restart_and_install<R: Runtime>(app: &AppHandle<R>, tool_id: String) -> InstallResult {
+    if find_tool(&tool_id).is_none() {
+        return LauncherError::UnknownTool(tool_id).into();
+    }
+
+    let was_running = is_tool_running(&tool_id);
+    if was_running {
+        if let Err(e) = supervisor::stop(app, &tool_id) {
+            return e.into();
+        }
+        if !wait_for_exit(&tool_id, QUIT_TIMEOUT) {
+            logging::error(
+                "restart_and_update",
+                &LauncherError::ToolRunning(tool_id.clone()),
+                json!({ "tool_id": tool_id }),
+            );
+            return LauncherError::ToolRunning(tool_id).into();
+        }
+    }
+
+    let mut result = install_latest(app, &tool_id);
+    if was_running {
+        // Bring the tool back even if the update failed, so the user isn't left without it
+        if let Err(e) = supervisor::launch_supervised(app, &tool_id, &LaunchOptions::default()) {
+            result.message = format!("{} (relaunch failed: {})", result.message, e);
+        }
+    }
+    result
+}
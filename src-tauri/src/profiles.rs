@@ -0,0 +1,201 @@
+// Named launch profiles, e.g. "Project X watch folder", combining a tool's
+// built-in profiles with ones the user saved in config.json
+
+use std::path::PathBuf;
+
+use serde_json::json;
+use tauri::{AppHandle, Runtime};
+
+use crate::error::LauncherError;
+use crate::logging;
+use crate::supervisor::{launch_supervised, LaunchOptions};
+use crate::tools::{find_tool, merge_launch_profiles, LaunchProfile};
+use crate::tray::refresh_tray;
+use crate::{load_config, tool_app_path, update_config, ActionResult};
+
+pub fn launch_profiles(tool_id: &str) -> Vec<LaunchProfile> {
+    let Some(tool) = find_tool(tool_id) else {
+        return Vec::new();
+    };
+    let custom = load_config()
+        .tool_settings
+        .remove(tool_id)
+        .map(|settings| settings.profiles)
+        .unwrap_or_default();
+    merge_launch_profiles(tool, &custom)
+}
+
+// Substitute `{home}` and `{tool_dir}`; anything else is left as written
+fn expand(value: &str, tool_dir: &str) -> String {
+    let home = dirs::home_dir().unwrap_or_default();
+    value
+        .replace("{home}", &home.to_string_lossy())
+        .replace("{tool_dir}", tool_dir)
+}
+
+fn launch_options(tool_id: &str, profile: &LaunchProfile) -> LaunchOptions {
+    let tool_dir = tool_app_path(tool_id)
+        .map(|path| path.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    LaunchOptions {
+        args: profile
+            .args
+            .iter()
+            .map(|arg| expand(arg, &tool_dir))
+            .collect(),
+        env: profile
+            .env
+            .iter()
+            .map(|(key, value)| (key.clone(), expand(value, &tool_dir)))
+            .collect(),
+        working_dir: profile
+            .working_dir
+            .as_deref()
+            .map(|dir| PathBuf::from(expand(dir, &tool_dir))),
+    }
+}
+
+pub fn launch_profile<R: Runtime>(
+    app: &AppHandle<R>,
+    tool_id: &str,
+    profile_name: &str,
+) -> Result<String, LauncherError> {
+    if find_tool(tool_id).is_none() {
+        return Err(LauncherError::UnknownTool(tool_id.to_string()));
+    }
+    let profile = launch_profiles(tool_id)
+        .into_iter()
+        .find(|profile| profile.name == profile_name)
+        .ok_or_else(|| {
+            LauncherError::InvalidInput(format!("Unknown launch profile: {}", profile_name))
+        })?;
+
+    let options = launch_options(tool_id, &profile);
+    if let Some(dir) = options.working_dir.as_ref().filter(|dir| !dir.is_dir()) {
+        return Err(LauncherError::InvalidInput(format!(
+            "Working directory {} does not exist",
+            dir.display()
+        )));
+    }
+
+    logging::info(
+        "launch_profile",
+        json!({ "tool_id": tool_id, "profile": profile_name }),
+    );
+    launch_supervised(app, tool_id, &options)
+}
+
+#[tauri::command]
+pub fn get_launch_profiles(tool_id: String) -> Vec<LaunchProfile> {
+    launch_profiles(&tool_id)
+}
+
+#[tauri::command]
+pub fn launch_tool_profile<R: Runtime>(
+    app: AppHandle<R>,
+    tool_id: String,
+    profile: String,
+) -> ActionResult {
+    launch_profile(&app, &tool_id, &profile).into()
+}
+
+// Adds a profile or replaces the one with the same name
+#[tauri::command]
+pub fn save_launch_profile<R: Runtime>(
+    app: AppHandle<R>,
+    tool_id: String,
+    profile: LaunchProfile,
+) -> ActionResult {
+    if find_tool(&tool_id).is_none() {
+        return LauncherError::UnknownTool(tool_id).into();
+    }
+    if profile.name.trim().is_empty() {
+        return LauncherError::InvalidInput("Profile name is required".to_string()).into();
+    }
+
+    let name = profile.name.clone();
+    let result = update_config(|config| {
+        let profiles = &mut config.tool_settings.entry(tool_id).or_default().profiles;
+        let profile = LaunchProfile {
+            builtin: false,
+            ..profile
+        };
+        match profiles.iter_mut().find(|p| p.name == profile.name) {
+            Some(existing) => *existing = profile,
+            None => profiles.push(profile),
+        }
+    });
+    refresh_tray(&app);
+    result.map(|_| format!("Saved profile {}", name)).into()
+}
+
+// Built-in profiles can't be deleted, only their overrides
+#[tauri::command]
+pub fn delete_launch_profile<R: Runtime>(
+    app: AppHandle<R>,
+    tool_id: String,
+    name: String,
+) -> ActionResult {
+    let result = update_config(|config| {
+        let Some(settings) = config.tool_settings.get_mut(&tool_id) else {
+            return false;
+        };
+        let before = settings.profiles.len();
+        settings.profiles.retain(|profile| profile.name != name);
+        settings.profiles.len() != before
+    });
+    refresh_tray(&app);
+
+    match result {
+        Ok(true) => Ok(format!("Deleted profile {}", name)),
+        Ok(false) => Err(LauncherError::InvalidInput(format!(
+            "Unknown launch profile: {}",
+            name
+        ))),
+        Err(e) => Err(e),
+    }
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOOL_DIR: &str = "/Apps/Sync.app";
+
+    fn home() -> String {
+        dirs::home_dir()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn expands_home_and_tool_dir() {
+        assert_eq!(
+            expand("{home}/Projects/X", TOOL_DIR),
+            format!("{}/Projects/X", home())
+        );
+        assert_eq!(
+            expand("{tool_dir}/Contents/Resources", TOOL_DIR),
+            "/Apps/Sync.app/Contents/Resources"
+        );
+        assert_eq!(
+            expand("--config={tool_dir}/a:{tool_dir}/b", TOOL_DIR),
+            "--config=/Apps/Sync.app/a:/Apps/Sync.app/b"
+        );
+    }
+
+    #[test]
+    fn leaves_unknown_placeholders_as_written() {
+        for value in ["{version}", "{HOME}/x", "{tool_dir", "{}", "--watch", ""] {
+            assert_eq!(expand(value, TOOL_DIR), value);
+        }
+    }
+
+    #[test]
+    fn missing_tool_dir_expands_to_nothing() {
+        assert_eq!(expand("{tool_dir}/bin", ""), "/bin");
+    }
+}
@@ -0,0 +1,154 @@
+// Recovery from an unreadable config.json. The bad file is moved aside, whatever
+// still parses is kept, and tool entries are rebuilt from the apps folder.
+// Shared manifests are never recovered here; see `config::load_manifest_at`.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::bundle::{read_bundle_version, read_version_marker_in};
+use crate::config::{migrate, write_config_at, ToolRecord, ToolsConfig};
+use crate::error::LauncherError;
+use crate::location::install_location_for;
+use crate::logging;
+use crate::now_secs;
+use crate::tools::TOOLS;
+
+// Recorded when the installed version can't be determined; shows up as an update
+const UNKNOWN_VERSION: &str = "unknown";
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ConfigRecovery {
+    pub error: LauncherError,
+    pub backup_path: Option<PathBuf>,
+    pub recovered_tools: HashMap<String, String>, // tool_id -> version found on disk
+    pub recovered_at: u64,
+}
+
+// Last recovery this session, until the UI dismisses it
+static LAST_RECOVERY: Mutex<Option<ConfigRecovery>> = Mutex::new(None);
+
+// The caller holds the config lock, so no other load can move the same file
+// aside and no update can land between the backup and the rebuilt write
+pub fn recover_config(config_path: &Path, error: LauncherError) -> ToolsConfig {
+    let content = fs::read(config_path).unwrap_or_default();
+    let recovered_at = now_secs();
+
+    let file_name = config_path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy();
+    let backup = config_path.with_file_name(format!("{}.corrupt-{}", file_name, recovered_at));
+    let backup_path = match fs::rename(config_path, &backup) {
+        Ok(()) => Some(backup),
+        Err(e) => {
+            // Without a backup, writing a rebuilt config would destroy the original
+            let e = LauncherError::from_io("Failed to back up corrupt config", e);
+            logging::error("config_recovery", &e, json!({ "path": config_path }));
+            return salvage(&content);
+        }
+    };
+
+    let mut config = salvage(&content);
+    // The salvaged install root, not the default one the missing config implies
+    let location = install_location_for(&config);
+    let apps_dir = location.apps_dir();
+    let mut recovered_tools = HashMap::new();
+    for tool in TOOLS {
+        let app_path = apps_dir.join(tool.app_name);
+        // A shared apps folder's installs are recorded in its manifest, not here
+        if location.system_wide || config.tools.contains_key(tool.id) || !app_path.exists() {
+            continue;
+        }
+
+        let version = read_version_marker_in(&apps_dir, tool.id)
+            .or_else(|| read_bundle_version(&app_path))
+            .unwrap_or_else(|| UNKNOWN_VERSION.to_string());
+        config
+            .tools
+            .insert(tool.id.to_string(), ToolRecord::new(&version));
+        recovered_tools.insert(tool.id.to_string(), version);
+    }
+
+    if let Err(e) = write_config_at(config_path, &config) {
+        let e = LauncherError::from_io("Failed to save recovered config", e);
+        logging::error("config_recovery", &e, json!({}));
+    }
+
+    let recovery = ConfigRecovery {
+        error,
+        backup_path,
+        recovered_tools,
+        recovered_at,
+    };
+    logging::error(
+        "config_recovery",
+        &recovery.error,
+        json!({
+            "backup_path": recovery.backup_path,
+            "recovered_tools": recovery.recovered_tools,
+        }),
+    );
+    *LAST_RECOVERY.lock().unwrap_or_else(|e| e.into_inner()) = Some(recovery);
+
+    config
+}
+
+// Keeps every top-level field that still deserializes on its own
+fn salvage(content: &[u8]) -> ToolsConfig {
+    let Ok(value) = serde_json::from_slice::<Value>(content) else {
+        return ToolsConfig::default();
+    };
+    let Value::Object(fields) = migrate(value.clone()).unwrap_or(value) else {
+        return ToolsConfig::default();
+    };
+
+    let mut config = ToolsConfig::default();
+    if let Some(tools) = field(&fields, "tools") {
+        config.tools = tools;
+    }
+    if let Some(web_apps) = field(&fields, "web_apps") {
+        config.web_apps = web_apps;
+    }
+    if let Some(geometry) = field(&fields, "window_geometry") {
+        config.window_geometry = geometry;
+    }
+    if let Some(install_root) = field(&fields, "install_root") {
+        config.install_root = install_root;
+    }
+    if let Some(system_wide) = field(&fields, "system_wide") {
+        config.system_wide = system_wide;
+    }
+    if let Some(update_checks) = field(&fields, "update_checks") {
+        config.update_checks = update_checks;
+    }
+    if let Some(update_policy) = field(&fields, "update_policy") {
+        config.update_policy = update_policy;
+    }
+    if let Some(tool_settings) = field(&fields, "tool_settings") {
+        config.tool_settings = tool_settings;
+    }
+    config
+}
+
+fn field<T: DeserializeOwned>(fields: &Map<String, Value>, name: &str) -> Option<T> {
+    serde_json::from_value(fields.get(name)?.clone()).ok()
+}
+
+#[tauri::command]
+pub fn get_config_recovery() -> Option<ConfigRecovery> {
+    LAST_RECOVERY
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+#[tauri::command]
+pub fn dismiss_config_recovery() {
+    *LAST_RECOVERY.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
@@ -0,0 +1,119 @@
+// Where DaVinci Resolve looks for user scripts and Fusion bundles. Scripts
+// show up under Workspace > Scripts > <category>; .drfx bundles are read
+// from the Templates folder.
+
+use std::path::{Path, PathBuf};
+
+use crate::error::LauncherError;
+use crate::tools::{ArtifactKind, ToolDefinition};
+
+const SCRIPT_EXTENSIONS: &[&str] = &["py", "lua"];
+const BUNDLE_EXTENSION: &str = "drfx";
+
+// Per-user Fusion folder for the current OS
+pub fn fusion_dir() -> PathBuf {
+    let data_dir = dirs::data_dir().unwrap_or_default();
+    if cfg!(target_os = "windows") {
+        data_dir
+            .join("Blackmagic Design")
+            .join("DaVinci Resolve")
+            .join("Support")
+            .join("Fusion")
+    } else if cfg!(target_os = "macos") {
+        data_dir
+            .join("Blackmagic Design")
+            .join("DaVinci Resolve")
+            .join("Fusion")
+    } else {
+        data_dir.join("DaVinciResolve").join("Fusion")
+    }
+}
+
+// Where a release file goes, or None if Resolve wouldn't load it
+pub fn destination(file_name: &str, category: &str) -> Option<PathBuf> {
+    destination_in(&fusion_dir(), file_name, category)
+}
+
+fn destination_in(fusion_dir: &Path, file_name: &str, category: &str) -> Option<PathBuf> {
+    let extension = Path::new(file_name).extension()?.to_str()?.to_lowercase();
+    if SCRIPT_EXTENSIONS.contains(&extension.as_str()) {
+        Some(fusion_dir.join("Scripts").join(category).join(file_name))
+    } else if extension == BUNDLE_EXTENSION {
+        Some(fusion_dir.join("Templates").join(file_name))
+    } else {
+        None
+    }
+}
+
+// The tool's main file, which stands in for the app path
+pub fn script_path(tool: &ToolDefinition) -> Option<PathBuf> {
+    match tool.kind {
+        ArtifactKind::ResolveScript { category } => destination(tool.app_name, category),
+        ArtifactKind::App => None,
+    }
+}
+
+// Scripts run inside Resolve, so there's nothing for the launcher to start
+pub fn check_launchable(tool: &ToolDefinition) -> Result<(), LauncherError> {
+    match tool.kind {
+        ArtifactKind::App => Ok(()),
+        ArtifactKind::ResolveScript { .. } => Err(LauncherError::Launch(format!(
+            "{} runs from Workspace > Scripts in DaVinci Resolve",
+            tool.name
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{tool, TempDir};
+
+    #[test]
+    fn scripts_go_into_their_category() {
+        let fusion = TempDir::new("resolve-scripts");
+        assert_eq!(
+            destination_in(fusion.path(), "Sync.py", "Utility"),
+            Some(fusion.join("Scripts/Utility/Sync.py"))
+        );
+        assert_eq!(
+            destination_in(fusion.path(), "Conform.LUA", "Edit"),
+            Some(fusion.join("Scripts/Edit/Conform.LUA"))
+        );
+    }
+
+    #[test]
+    fn bundles_go_into_templates() {
+        let fusion = TempDir::new("resolve-bundles");
+        assert_eq!(
+            destination_in(fusion.path(), "Titles.drfx", "Utility"),
+            Some(fusion.join("Templates/Titles.drfx"))
+        );
+    }
+
+    #[test]
+    fn other_files_are_ignored() {
+        let fusion = TempDir::new("resolve-other");
+        for file_name in ["README.md", "Sync.py.sha256", "Sync.zip", "py", "Makefile"] {
+            assert_eq!(destination_in(fusion.path(), file_name, "Utility"), None);
+        }
+    }
+
+    #[test]
+    fn only_scripts_are_refused_a_launch() {
+        static SCRIPT: ToolDefinition = ToolDefinition {
+            kind: ArtifactKind::ResolveScript {
+                category: "Utility",
+            },
+            app_name: "Sync.py",
+            ..tool("sync-script")
+        };
+        assert!(check_launchable(&SCRIPT).is_err());
+        assert!(check_launchable(&tool("app")).is_ok());
+        assert_eq!(script_path(&tool("app")), None);
+        assert_eq!(
+            script_path(&SCRIPT),
+            Some(fusion_dir().join("Scripts/Utility/Sync.py"))
+        );
+    }
+}
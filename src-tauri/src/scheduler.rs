@@ -0,0 +1,208 @@
+// Periodic update checks for every installed tool and the launcher itself.
+// Runs on its own thread so the tray stays current while the window is hidden.
+
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tauri_plugin_updater::UpdaterExt;
+
+use crate::error::LauncherError;
+use crate::installer::tool_status;
+use crate::logging;
+use crate::policy::{self, UpdateMode};
+use crate::{
+    get_installed_tools, load_config, sync_update_indicators, update_config, ActionResult, AppState,
+};
+
+// Let the app finish starting before the first check
+const STARTUP_DELAY: Duration = Duration::from_secs(60);
+const MIN_INTERVAL_MINUTES: u64 = 15;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpdateCheckSettings {
+    pub enabled: bool,
+    pub interval_minutes: u64,
+    pub jitter_minutes: u64, // spreads checks out so a studio's machines don't all hit GitHub at once
+}
+
+impl Default for UpdateCheckSettings {
+    fn default() -> Self {
+        UpdateCheckSettings {
+            enabled: true,
+            interval_minutes: 240,
+            jitter_minutes: 15,
+        }
+    }
+}
+
+pub enum SchedulerMessage {
+    CheckNow,
+    Reschedule, // settings changed
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct UpdateCheckSummary {
+    pub tool_updates: HashMap<String, String>, // tool_id -> latest version
+    pub launcher_update: Option<String>,
+    pub errors: HashMap<String, LauncherError>, // tool_id or "launcher" -> failure
+}
+
+pub fn start<R: Runtime>(app: AppHandle<R>) {
+    let (sender, receiver) = mpsc::channel();
+    *app.state::<AppState>().scheduler.lock().unwrap() = Some(sender);
+
+    thread::spawn(move || {
+        let mut next_check = Instant::now() + STARTUP_DELAY;
+        loop {
+            let timeout = next_check.saturating_duration_since(Instant::now());
+            let message = receiver.recv_timeout(timeout);
+            // Loaded after waiting, so a Reschedule sees the settings that sent it
+            let settings = load_config().update_checks;
+
+            match message {
+                Ok(SchedulerMessage::CheckNow) => {}
+                Ok(SchedulerMessage::Reschedule) => {
+                    next_check = Instant::now() + next_delay(&settings);
+                    continue;
+                }
+                Err(RecvTimeoutError::Timeout) if !settings.enabled => {
+                    next_check = Instant::now() + next_delay(&settings);
+                    continue;
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+
+            run_update_check(&app);
+            next_check = Instant::now() + next_delay(&settings);
+        }
+    });
+}
+
+fn next_delay(settings: &UpdateCheckSettings) -> Duration {
+    let interval = settings.interval_minutes.max(MIN_INTERVAL_MINUTES) * 60;
+    let jitter = settings.jitter_minutes.min(settings.interval_minutes / 2) * 60;
+    if jitter == 0 {
+        return Duration::from_secs(interval);
+    }
+
+    // Anywhere in interval ± jitter; RandomState is seeded randomly per instance
+    let random = RandomState::new().build_hasher().finish();
+    let offset = random % (2 * jitter + 1);
+    Duration::from_secs(interval - jitter + offset)
+}
+
+pub fn run_update_check<R: Runtime>(app: &AppHandle<R>) -> UpdateCheckSummary {
+    let started = Instant::now();
+    let mut summary = UpdateCheckSummary {
+        tool_updates: HashMap::new(),
+        launcher_update: None,
+        errors: HashMap::new(),
+    };
+
+    let state = app.state::<AppState>();
+    let policies = load_config().update_policy;
+    for tool_id in get_installed_tools() {
+        if policies.for_tool(&tool_id).mode == UpdateMode::Manual {
+            continue;
+        }
+
+        let status = tool_status(&tool_id);
+        if let Some(error) = status.error {
+            // Keep whatever we knew before rather than clearing it on a failed check
+            summary.errors.insert(tool_id, error);
+            continue;
+        }
+
+        let mut available_updates = state.available_updates.lock().unwrap();
+        match status.latest_version.filter(|_| status.has_update) {
+            Some(version) => {
+                available_updates.insert(tool_id.clone(), version.clone());
+                summary.tool_updates.insert(tool_id, version);
+            }
+            None => {
+                available_updates.remove(&tool_id);
+            }
+        }
+    }
+
+    match check_launcher_update(app) {
+        Ok(version) => {
+            *state.launcher_update.lock().unwrap() = version.clone();
+            summary.launcher_update = version;
+        }
+        Err(e) => {
+            summary.errors.insert("launcher".to_string(), e);
+        }
+    }
+
+    sync_update_indicators(app);
+    logging::info(
+        "scheduled_check",
+        json!({
+            "tool_updates": summary.tool_updates,
+            "launcher_update": summary.launcher_update,
+            "errors": summary.errors,
+            "duration_ms": started.elapsed().as_millis() as u64,
+        }),
+    );
+    let _ = app.emit("updates-checked", &summary);
+    policy::evaluate(app);
+
+    summary
+}
+
+fn check_launcher_update<R: Runtime>(app: &AppHandle<R>) -> Result<Option<String>, LauncherError> {
+    let updater = app
+        .updater()
+        .map_err(|e| LauncherError::Config(format!("Updater unavailable: {}", e)))?;
+    tauri::async_runtime::block_on(updater.check())
+        .map(|update| update.map(|update| update.version))
+        .map_err(|e| LauncherError::Network(format!("Failed to check for launcher updates: {}", e)))
+}
+
+pub fn send<R: Runtime>(app: &AppHandle<R>, message: SchedulerMessage) {
+    if let Some(sender) = app.state::<AppState>().scheduler.lock().unwrap().as_ref() {
+        let _ = sender.send(message);
+    }
+}
+
+// Returns immediately; results arrive as an `updates-checked` event
+#[tauri::command]
+pub fn check_for_updates_now<R: Runtime>(app: AppHandle<R>) {
+    send(&app, SchedulerMessage::CheckNow);
+}
+
+#[tauri::command]
+pub fn get_update_check_settings() -> UpdateCheckSettings {
+    load_config().update_checks
+}
+
+#[tauri::command]
+pub fn set_update_check_settings<R: Runtime>(
+    app: AppHandle<R>,
+    settings: UpdateCheckSettings,
+) -> ActionResult {
+    if settings.interval_minutes < MIN_INTERVAL_MINUTES {
+        return LauncherError::InvalidInput(format!(
+            "Check interval must be at least {} minutes",
+            MIN_INTERVAL_MINUTES
+        ))
+        .into();
+    }
+
+    let result = update_config(|config| config.update_checks = settings);
+    if result.is_ok() {
+        send(&app, SchedulerMessage::Reschedule);
+    }
+    result
+        .map(|_| "Saved update check settings".to_string())
+        .into()
+}
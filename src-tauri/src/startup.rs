@@ -0,0 +1,140 @@
+// Starts the tools marked "start with launcher" when the launcher itself
+// starts, after the tools they depend on and with optional delays
+
+use std::collections::{HashMap, HashSet};
+use std::thread;
+use std::time::Duration;
+
+use serde_json::json;
+use tauri::{AppHandle, Runtime};
+
+use crate::config::{StartupSettings, ToolsConfig};
+use crate::error::LauncherError;
+use crate::logging;
+use crate::process::is_tool_running;
+use crate::profiles::launch_profile;
+use crate::supervisor::{launch_supervised, LaunchOptions};
+use crate::tools::{find_tool, TOOLS};
+use crate::{get_installed_tools, load_config, update_config, ActionResult};
+
+// Tools to start, each after the ones it lists in `after`. Dependencies that
+// aren't being started are only ordering hints and are ignored.
+pub fn startup_plan(config: &ToolsConfig) -> Result<Vec<(String, StartupSettings)>, LauncherError> {
+    let installed = get_installed_tools();
+    let selected: HashMap<&str, &StartupSettings> = TOOLS
+        .iter()
+        .filter(|tool| installed.iter().any(|id| id == tool.id))
+        .filter_map(|tool| {
+            let settings = &config.tool_settings.get(tool.id)?.startup;
+            settings.enabled.then_some((tool.id, settings))
+        })
+        .collect();
+
+    let mut plan = Vec::new();
+    let mut done: HashSet<&str> = HashSet::new();
+    let mut visiting: HashSet<&str> = HashSet::new();
+
+    // Registry order decides between tools with no dependency on each other
+    for tool in TOOLS {
+        if selected.contains_key(tool.id) {
+            visit(tool.id, &selected, &mut done, &mut visiting, &mut plan)?;
+        }
+    }
+
+    Ok(plan)
+}
+
+// Depth-first, so each tool lands after everything it starts after
+fn visit<'a>(
+    tool_id: &'a str,
+    selected: &HashMap<&'a str, &'a StartupSettings>,
+    done: &mut HashSet<&'a str>,
+    visiting: &mut HashSet<&'a str>,
+    plan: &mut Vec<(String, StartupSettings)>,
+) -> Result<(), LauncherError> {
+    if done.contains(tool_id) {
+        return Ok(());
+    }
+    if !visiting.insert(tool_id) {
+        return Err(LauncherError::InvalidInput(format!(
+            "Startup order has a cycle through {}",
+            tool_id
+        )));
+    }
+
+    let settings = selected[tool_id];
+    for dependency in &settings.after {
+        if selected.contains_key(dependency.as_str()) {
+            visit(dependency, selected, done, visiting, plan)?;
+        }
+    }
+
+    visiting.remove(tool_id);
+    done.insert(tool_id);
+    plan.push((tool_id.to_string(), settings.clone()));
+    Ok(())
+}
+
+pub fn start<R: Runtime>(app: AppHandle<R>) {
+    let plan = match startup_plan(&load_config()) {
+        Ok(plan) => plan,
+        Err(e) => {
+            logging::error("startup", &e, json!({}));
+            return;
+        }
+    };
+    if plan.is_empty() {
+        return;
+    }
+
+    thread::spawn(move || {
+        for (tool_id, settings) in plan {
+            if settings.delay_secs > 0 {
+                thread::sleep(Duration::from_secs(settings.delay_secs));
+            }
+            if is_tool_running(&tool_id) {
+                continue;
+            }
+
+            let result = match &settings.profile {
+                Some(profile) => launch_profile(&app, &tool_id, profile),
+                None => launch_supervised(&app, &tool_id, &LaunchOptions::default()),
+            };
+            let fields = json!({ "tool_id": tool_id, "profile": settings.profile });
+            match &result {
+                Ok(_) => logging::info("startup_launch", fields),
+                Err(e) => logging::error("startup_launch", e, fields),
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub fn set_startup_settings(tool_id: String, settings: StartupSettings) -> ActionResult {
+    if find_tool(&tool_id).is_none() {
+        return LauncherError::UnknownTool(tool_id).into();
+    }
+    if let Some(unknown) = settings.after.iter().find(|id| find_tool(id).is_none()) {
+        return LauncherError::UnknownTool(unknown.clone()).into();
+    }
+    if settings.after.contains(&tool_id) {
+        return LauncherError::InvalidInput("A tool can't start after itself".to_string()).into();
+    }
+
+    // Check the new order before saving it, so a cycle can't break startup
+    let mut config = load_config();
+    config
+        .tool_settings
+        .entry(tool_id.clone())
+        .or_default()
+        .startup = settings.clone();
+    if let Err(e) = startup_plan(&config) {
+        return e.into();
+    }
+
+    update_config(|config| {
+        config.tool_settings.entry(tool_id).or_default().startup = settings;
+    })
+    .map(|_| "Saved startup settings".to_string())
+    .into()
+}
@@ -0,0 +1,373 @@
+// Tracks the tools the launcher started: runs their executable directly so
+// it owns the process and its output, reports exits and crashes to the window, and
+// relaunches tools marked for auto-restart
+
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use serde_json::json;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use crate::bundle::bundle_executable;
+use crate::config::ToolSettings;
+use crate::error::LauncherError;
+use crate::logging;
+use crate::process;
+use crate::resolve;
+use crate::tool_logs;
+use crate::tools::find_tool;
+use crate::tray::refresh_tray;
+use crate::{launch, load_config, now_secs, tool_app_path, update_config, ActionResult, AppState};
+
+const MAX_RESTARTS: u32 = 5;
+// A tool that stayed up this long is healthy again and gets a fresh set of restarts
+const RESTART_RESET_AFTER: Duration = Duration::from_secs(5 * 60);
+const RESTART_BACKOFF: Duration = Duration::from_secs(2);
+
+// How to start a tool, kept so a crashed tool restarts the same way
+#[derive(Debug, Clone, Default)]
+pub struct LaunchOptions {
+    pub args: Vec<String>,
+    pub env: Vec<(String, String)>,
+    pub working_dir: Option<PathBuf>,
+}
+
+impl LaunchOptions {
+    pub fn with_args(args: &[String]) -> Self {
+        LaunchOptions {
+            args: args.to_vec(),
+            ..LaunchOptions::default()
+        }
+    }
+}
+
+pub struct SupervisedProcess {
+    pub pid: u32,
+    pub started_at: u64, // unix seconds
+    pub options: LaunchOptions,
+    pub restarts: u32,    // consecutive crash restarts
+    pub stopping: bool,   // quit was requested, so the exit isn't a crash
+    pub restarting: bool, // crashed and waiting out the backoff; `pid` has exited
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RunningTool {
+    pub tool_id: String,
+    pub pid: u32,
+    pub started_at: u64,
+    pub restarts: u32,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ToolExit {
+    pub tool_id: String,
+    pub code: Option<i32>, // None when killed by a signal
+    pub crashed: bool,
+    pub restarting: bool,
+}
+
+pub fn is_supervised<R: Runtime>(app: &AppHandle<R>, tool_id: &str) -> bool {
+    app.state::<AppState>()
+        .supervised
+        .lock()
+        .unwrap()
+        .contains_key(tool_id)
+}
+
+// Start a tool under supervision, falling back to an untracked launch when
+// there's no executable to run directly
+pub fn launch_supervised<R: Runtime>(
+    app: &AppHandle<R>,
+    tool_id: &str,
+    options: &LaunchOptions,
+) -> Result<String, LauncherError> {
+    let tool = find_tool(tool_id).ok_or_else(|| LauncherError::UnknownTool(tool_id.to_string()))?;
+    resolve::check_launchable(tool)?;
+    if is_supervised(app, tool_id) {
+        return Ok(format!("{} is already running", tool.name));
+    }
+    // Started outside the launcher, or before it restarted. `open` brings that
+    // copy forward instead of starting a second one on the same media.
+    if process::is_tool_running(tool_id) {
+        return launch(tool_id, &[]).map(|_| format!("{} is already running", tool.name));
+    }
+
+    let app_path =
+        tool_app_path(tool_id).ok_or_else(|| LauncherError::UnknownTool(tool_id.to_string()))?;
+    if !app_path.exists() {
+        return Err(LauncherError::NotInstalled(tool_id.to_string()));
+    }
+    // `open` can't pass the environment or working directory through
+    let Some(executable) = bundle_executable(&app_path) else {
+        return launch(tool_id, &options.args);
+    };
+
+    let result = spawn(app, tool_id, &executable, options, 0);
+    let fields = json!({ "tool_id": tool_id, "args": options.args });
+    match &result {
+        Ok(pid) => logging::info("launch", with_pid(fields, *pid)),
+        Err(e) => logging::error("launch", e, fields),
+    }
+    result.map(|_| "Launched app".to_string())
+}
+
+fn spawn<R: Runtime>(
+    app: &AppHandle<R>,
+    tool_id: &str,
+    executable: &Path,
+    options: &LaunchOptions,
+    restarts: u32,
+) -> Result<u32, LauncherError> {
+    let mut command = Command::new(executable);
+    command
+        .args(&options.args)
+        .envs(options.env.iter().cloned());
+    if let Some(working_dir) = &options.working_dir {
+        command.current_dir(working_dir);
+    }
+    // Not pipes: the tool would get SIGPIPE on its next write once the launcher quits
+    let output = tool_logs::open_output(tool_id)
+        .and_then(|(file, offset)| Some((file.try_clone().ok()?, file, offset)));
+    let offset = match output {
+        Some((stdout, stderr, offset)) => {
+            command.stdout(stdout).stderr(stderr);
+            Some(offset)
+        }
+        None => {
+            command.stdout(Stdio::null()).stderr(Stdio::null());
+            None
+        }
+    };
+    let child = command
+        .stdin(Stdio::null())
+        .spawn()
+        .map_err(|e| LauncherError::Launch(format!("Failed to launch: {}", e)))?;
+
+    let pid = child.id();
+    app.state::<AppState>().supervised.lock().unwrap().insert(
+        tool_id.to_string(),
+        SupervisedProcess {
+            pid,
+            started_at: now_secs(),
+            options: options.clone(),
+            restarts,
+            stopping: false,
+            restarting: false,
+        },
+    );
+    refresh_tray(app);
+    tool_logs::append_note(tool_id, &format!("started (pid {})", pid));
+    if let Some(offset) = offset {
+        tool_logs::follow(app, tool_id, pid, offset);
+    }
+
+    let app = app.clone();
+    let tool_id = tool_id.to_string();
+    let executable = executable.to_path_buf();
+    thread::spawn(move || watch(&app, &tool_id, &executable, child));
+
+    Ok(pid)
+}
+
+fn watch<R: Runtime>(app: &AppHandle<R>, tool_id: &str, executable: &Path, mut child: Child) {
+    let started = Instant::now();
+    let status = child.wait();
+
+    let state = app.state::<AppState>();
+    let Some(mut process) = state.supervised.lock().unwrap().remove(tool_id) else {
+        return;
+    };
+
+    let code = status.as_ref().ok().and_then(ExitStatus::code);
+    let crashed = !process.stopping && !status.as_ref().is_ok_and(ExitStatus::success);
+    let restarts = match started.elapsed() >= RESTART_RESET_AFTER {
+        true => 0,
+        false => process.restarts,
+    };
+    let auto_restart = load_config()
+        .tool_settings
+        .get(tool_id)
+        .is_some_and(|settings| settings.auto_restart);
+    let restarting = crashed && auto_restart && restarts < MAX_RESTARTS;
+
+    let fields = json!({
+        "tool_id": tool_id,
+        "code": code,
+        "crashed": crashed,
+        "restarts": restarts,
+        "uptime_secs": started.elapsed().as_secs(),
+    });
+    tool_logs::append_note(
+        tool_id,
+        &match code {
+            Some(code) => format!("exited with code {}", code),
+            None => "exited".to_string(),
+        },
+    );
+    if crashed {
+        let error = LauncherError::Launch(format!("{} exited unexpectedly", tool_id));
+        logging::error("tool_exited", &error, fields);
+    } else {
+        logging::info("tool_exited", fields);
+    }
+
+    let _ = app.emit(
+        "tool-exited",
+        ToolExit {
+            tool_id: tool_id.to_string(),
+            code,
+            crashed,
+            restarting,
+        },
+    );
+    refresh_tray(app);
+
+    if !restarting {
+        return;
+    }
+
+    // Still supervised through the backoff, so quitting it cancels the restart
+    let options = process.options.clone();
+    process.restarting = true;
+    state
+        .supervised
+        .lock()
+        .unwrap()
+        .insert(tool_id.to_string(), process);
+    thread::sleep(RESTART_BACKOFF * (restarts + 1));
+
+    // Quit, or started again by hand, while we waited
+    let cancelled = state
+        .supervised
+        .lock()
+        .unwrap()
+        .get(tool_id)
+        .is_none_or(|process| !process.restarting || process.stopping);
+    if cancelled || process::is_tool_running(tool_id) {
+        remove_pending_restart(app, tool_id);
+        logging::info("auto_restart_cancelled", json!({ "tool_id": tool_id }));
+        return;
+    }
+    // The pending entry keeps other launches out until `spawn` replaces it
+    if let Err(e) = spawn(app, tool_id, executable, &options, restarts + 1) {
+        remove_pending_restart(app, tool_id);
+        logging::error("auto_restart", &e, json!({ "tool_id": tool_id }));
+    }
+}
+
+fn remove_pending_restart<R: Runtime>(app: &AppHandle<R>, tool_id: &str) {
+    let state = app.state::<AppState>();
+    let mut supervised = state.supervised.lock().unwrap();
+    if supervised
+        .get(tool_id)
+        .is_some_and(|process| process.restarting)
+    {
+        supervised.remove(tool_id);
+    }
+    drop(supervised);
+    refresh_tray(app);
+}
+
+// Ask a tool to quit, marking supervised ones so the exit isn't treated as a crash
+pub fn stop<R: Runtime>(app: &AppHandle<R>, tool_id: &str) -> Result<(), LauncherError> {
+    let pid = {
+        let state = app.state::<AppState>();
+        let mut supervised = state.supervised.lock().unwrap();
+        supervised.get_mut(tool_id).map(|process| {
+            process.stopping = true;
+            (!process.restarting).then_some(process.pid)
+        })
+    };
+
+    let result = match pid {
+        Some(Some(pid)) => terminate(pid),
+        Some(None) => Ok(()), // a pending restart; marking it is enough
+        None => process::quit(tool_id),
+    };
+    result.map_err(|e| LauncherError::from_io("Failed to quit tool", e))
+}
+
+#[cfg(unix)]
+fn terminate(pid: u32) -> std::io::Result<()> {
+    Command::new("kill")
+        .args(["-TERM", &pid.to_string()])
+        .output()
+        .map(|_| ())
+}
+
+#[cfg(windows)]
+fn terminate(pid: u32) -> std::io::Result<()> {
+    Command::new("taskkill")
+        .args(["/PID", &pid.to_string()])
+        .output()
+        .map(|_| ())
+}
+
+fn with_pid(mut fields: serde_json::Value, pid: u32) -> serde_json::Value {
+    fields["pid"] = json!(pid);
+    fields
+}
+
+#[tauri::command]
+pub fn running_tools<R: Runtime>(app: AppHandle<R>) -> Vec<RunningTool> {
+    let mut running: Vec<RunningTool> = app
+        .state::<AppState>()
+        .supervised
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(_, process)| !process.restarting)
+        .map(|(tool_id, process)| RunningTool {
+            tool_id: tool_id.clone(),
+            pid: process.pid,
+            started_at: process.started_at,
+            restarts: process.restarts,
+        })
+        .collect();
+    running.sort_by(|a, b| a.tool_id.cmp(&b.tool_id));
+    running
+}
+
+#[tauri::command]
+pub fn quit_tool<R: Runtime>(app: AppHandle<R>, tool_id: String) -> ActionResult {
+    let Some(tool) = find_tool(&tool_id) else {
+        return LauncherError::UnknownTool(tool_id).into();
+    };
+    let result = stop(&app, &tool_id);
+    let fields = json!({ "tool_id": tool_id });
+    match &result {
+        Ok(_) => logging::info("quit_tool", fields),
+        Err(e) => logging::error("quit_tool", e, fields),
+    }
+    result.map(|_| format!("Quit {}", tool.name)).into()
+}
+
+#[tauri::command]
+pub fn get_tool_settings(tool_id: String) -> ToolSettings {
+    load_config()
+        .tool_settings
+        .remove(&tool_id)
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+pub fn set_auto_restart(tool_id: String, enabled: bool) -> ActionResult {
+    if find_tool(&tool_id).is_none() {
+        return LauncherError::UnknownTool(tool_id).into();
+    }
+    update_config(|config| {
+        config
+            .tool_settings
+            .entry(tool_id.clone())
+            .or_default()
+            .auto_restart = enabled;
+    })
+    .map(|_| match enabled {
+        true => "Auto-restart enabled".to_string(),
+        false => "Auto-restart disabled".to_string(),
+    })
+    .into()
+}
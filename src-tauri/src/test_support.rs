@@ -0,0 +1,47 @@
+// Helpers shared by the unit tests
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::tools::{ArtifactKind, ToolDefinition};
+
+// A scratch folder, removed when the test ends, pass or fail
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    pub fn new(name: &str) -> Self {
+        let dir =
+            std::env::temp_dir().join(format!("story-launcher-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        TempDir(dir)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+
+    pub fn join(&self, path: impl AsRef<Path>) -> PathBuf {
+        self.0.join(path)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+// A bare app tool; tests fill in what they need with `..tool(id)`
+pub const fn tool(id: &'static str) -> ToolDefinition {
+    ToolDefinition {
+        id,
+        name: id,
+        repo: "",
+        app_name: "",
+        kind: ArtifactKind::App,
+        known_locations: &[],
+        profiles: &[],
+        dependencies: &[],
+    }
+}
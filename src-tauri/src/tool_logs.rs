@@ -0,0 +1,174 @@
+// Output of tools the launcher started, one folder per tool under
+// ~/.story-tools/logs/<tool>/. New lines are also streamed to the window as
+// `tool-log` events while it's open.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use chrono::Local;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use crate::logging::rotate_files;
+use crate::tools::find_tool;
+use crate::{get_tool_logs_dir, AppState};
+
+const MAX_LOG_SIZE: u64 = 5 * 1024 * 1024;
+const MAX_ROTATED_LOGS: usize = 3;
+const FOLLOW_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ToolLogLine {
+    pub tool_id: String,
+    pub line: String,
+}
+
+fn log_path(tool_id: &str, index: usize) -> PathBuf {
+    match index {
+        0 => get_tool_logs_dir(tool_id).join("output.log"),
+        n => get_tool_logs_dir(tool_id).join(format!("output.{}.log", n)),
+    }
+}
+
+// output.log -> output.1.log -> ... -> output.3.log (dropped)
+fn rotate(tool_id: &str) -> io::Result<()> {
+    rotate_files(|index| log_path(tool_id, index), MAX_ROTATED_LOGS)
+}
+
+// A running tool keeps writing to the output.log it was given, so the file
+// can't be moved aside. Copy it out and truncate it instead; the tool opened
+// it for appending, so its next write lands at the new end. Lines written
+// between the copy and the truncate are lost.
+fn rotate_in_place(tool_id: &str) -> io::Result<()> {
+    rotate_files(|index| log_path(tool_id, index + 1), MAX_ROTATED_LOGS - 1)?;
+    fs::copy(log_path(tool_id, 0), log_path(tool_id, 1))?;
+    OpenOptions::new()
+        .write(true)
+        .open(log_path(tool_id, 0))?
+        .set_len(0)
+}
+
+// The log a new process writes its stdout and stderr straight into, so it
+// keeps running if the launcher quits, and the offset its output starts at.
+// While the launcher follows the process the log is rotated in place too.
+pub fn open_output(tool_id: &str) -> Option<(File, u64)> {
+    fs::create_dir_all(get_tool_logs_dir(tool_id)).ok()?;
+    let path = log_path(tool_id, 0);
+    if fs::metadata(&path).is_ok_and(|m| m.len() >= MAX_LOG_SIZE) {
+        let _ = rotate(tool_id);
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .ok()?;
+    let offset = file.metadata().map(|m| m.len()).unwrap_or(0);
+    Some((file, offset))
+}
+
+// Stream what process `pid` appends to the log from `offset` to the window,
+// and keep the log under MAX_LOG_SIZE, until it's no longer the supervised
+// copy of the tool
+pub fn follow<R: Runtime>(app: &AppHandle<R>, tool_id: &str, pid: u32, offset: u64) {
+    let app = app.clone();
+    let tool_id = tool_id.to_string();
+    thread::spawn(move || {
+        let path = log_path(&tool_id, 0);
+        let Ok(mut file) = File::open(&path) else {
+            return;
+        };
+        if file.seek(SeekFrom::Start(offset)).is_err() {
+            return;
+        }
+
+        // Tools don't always write UTF-8, so read raw lines and convert lossily
+        let mut reader = BufReader::new(file);
+        let mut buffer = Vec::new();
+        loop {
+            let running = is_supervised_pid(&app, &tool_id, pid);
+            // A line without its newline yet stays in the buffer until the rest arrives
+            while matches!(reader.read_until(b'\n', &mut buffer), Ok(n) if n > 0)
+                && buffer.ends_with(b"\n")
+            {
+                let line = String::from_utf8_lossy(&buffer).trim_end().to_string();
+                buffer.clear();
+                let _ = app.emit(
+                    "tool-log",
+                    ToolLogLine {
+                        tool_id: tool_id.clone(),
+                        line,
+                    },
+                );
+            }
+            if !running {
+                break;
+            }
+            // Everything up to here was streamed, so start over at the top
+            if fs::metadata(&path).is_ok_and(|m| m.len() >= MAX_LOG_SIZE)
+                && rotate_in_place(&tool_id).is_ok()
+                && reader.seek(SeekFrom::Start(0)).is_err()
+            {
+                break;
+            }
+            thread::sleep(FOLLOW_INTERVAL);
+        }
+    });
+}
+
+fn is_supervised_pid<R: Runtime>(app: &AppHandle<R>, tool_id: &str, pid: u32) -> bool {
+    app.state::<AppState>()
+        .supervised
+        .lock()
+        .unwrap()
+        .get(tool_id)
+        .is_some_and(|process| process.pid == pid && !process.restarting)
+}
+
+// A note from the launcher itself, e.g. that the tool started or exited.
+// Losing a note is better than failing a launch, so write errors are dropped.
+pub fn append_note(tool_id: &str, message: &str) {
+    let _ = fs::create_dir_all(get_tool_logs_dir(tool_id));
+    let Ok(mut file) = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log_path(tool_id, 0))
+    else {
+        return;
+    };
+    let _ = writeln!(
+        file,
+        "{} [launcher] {}",
+        Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+        message
+    );
+}
+
+// Last `lines` lines across the current and rotated files, oldest first
+#[tauri::command]
+pub fn tail_tool_log(tool_id: String, lines: Option<usize>) -> Vec<String> {
+    // The id becomes a path component, so only accept known tools
+    if find_tool(&tool_id).is_none() {
+        return Vec::new();
+    }
+
+    let limit = lines.unwrap_or(200);
+    let mut tail: Vec<String> = Vec::new();
+
+    for index in 0..=MAX_ROTATED_LOGS {
+        let Ok(file) = File::open(log_path(&tool_id, index)) else {
+            break;
+        };
+        let mut older: Vec<String> = BufReader::new(file).lines().map_while(Result::ok).collect();
+        older.append(&mut tail);
+        tail = older;
+        if tail.len() >= limit {
+            break;
+        }
+    }
+
+    let skip = tail.len().saturating_sub(limit);
+    tail.split_off(skip)
+}
@@ -0,0 +1,274 @@
+// Registry of local tools and web apps the launcher knows about
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+pub struct ToolDefinition {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub repo: &'static str,
+    pub app_name: &'static str, // bundle name, or the main file for Resolve scripts
+    pub kind: ArtifactKind,
+    pub known_locations: &'static [&'static str], // where manual installs usually live
+    pub profiles: &'static [LaunchProfileDefinition], // built-in launch profiles
+    pub dependencies: &'static [ToolDependency],  // installed before this tool
+}
+
+// What a tool's releases contain, which decides where it's installed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArtifactKind {
+    App, // an app bundle in the apps folder
+    // `.py`/`.lua` scripts and `.drfx` bundles for DaVinci Resolve, with
+    // scripts going into the Fusion Scripts `category` folder, e.g. "Utility"
+    #[allow(dead_code)] // no built-in tool is a Resolve script yet
+    ResolveScript {
+        category: &'static str,
+    },
+}
+
+// Another tool this one needs, e.g. a shared plugin, with a semver
+// requirement such as ">=1.2, <2" or "*"
+pub struct ToolDependency {
+    pub tool_id: &'static str,
+    pub version: &'static str,
+}
+
+pub const TOOLS: &[ToolDefinition] = &[ToolDefinition {
+    id: "resolve-sync",
+    name: "Resolve Sync Script",
+    repo: "joyrider00/spellbook-resolve-sync",
+    app_name: "Spellbook Resolve Sync.app",
+    kind: ArtifactKind::App,
+    known_locations: &[
+        "/Applications/Spellbook Resolve Sync.app",
+        "~/Applications/Spellbook Resolve Sync.app",
+    ],
+    profiles: &[],
+    dependencies: &[],
+}];
+
+pub fn find_tool(tool_id: &str) -> Option<&'static ToolDefinition> {
+    TOOLS.iter().find(|tool| tool.id == tool_id)
+}
+
+// Named ways to start a tool. Args, env values and the working directory may
+// use `{home}` and `{tool_dir}`, expanded at launch.
+pub struct LaunchProfileDefinition {
+    pub name: &'static str,
+    pub args: &'static [&'static str],
+    pub env: &'static [(&'static str, &'static str)],
+    pub working_dir: Option<&'static str>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LaunchProfile {
+    pub name: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    #[serde(default)]
+    pub working_dir: Option<String>,
+    #[serde(default)]
+    pub builtin: bool,
+}
+
+impl From<&LaunchProfileDefinition> for LaunchProfile {
+    fn from(def: &LaunchProfileDefinition) -> Self {
+        LaunchProfile {
+            name: def.name.to_string(),
+            args: def.args.iter().map(|arg| arg.to_string()).collect(),
+            env: def
+                .env
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+            working_dir: def.working_dir.map(str::to_string),
+            builtin: true,
+        }
+    }
+}
+
+// Built-in profiles followed by the user's; a user profile with a built-in name overrides it
+pub fn merge_launch_profiles(
+    tool: &ToolDefinition,
+    custom: &[LaunchProfile],
+) -> Vec<LaunchProfile> {
+    let mut profiles: Vec<LaunchProfile> = tool.profiles.iter().map(LaunchProfile::from).collect();
+
+    for profile in custom {
+        match profiles.iter_mut().find(|p| p.name == profile.name) {
+            Some(existing) => {
+                *existing = LaunchProfile {
+                    builtin: existing.builtin,
+                    ..profile.clone()
+                }
+            }
+            None => profiles.push(LaunchProfile {
+                builtin: false,
+                ..profile.clone()
+            }),
+        }
+    }
+
+    profiles
+}
+
+// Web apps shipped with the launcher; users can add their own bookmarks on top
+pub struct WebAppDefinition {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub url: &'static str,
+    pub icon: &'static str,
+}
+
+pub const WEB_APPS: &[WebAppDefinition] = &[
+    WebAppDefinition {
+        id: "spellbook",
+        name: "Spellbook",
+        url: "https://spellbook.story.inc",
+        icon: "spellbook",
+    },
+    WebAppDefinition {
+        id: "portal",
+        name: "Story Portal",
+        url: "https://portal.story.inc",
+        icon: "portal",
+    },
+];
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WebApp {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    #[serde(default)]
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub browser: Option<String>, // None opens the system default browser
+    #[serde(default)]
+    pub profile: Option<String>, // Chromium profile directory, requires `browser`
+    #[serde(default)]
+    pub open_in_window: bool, // open in a launcher-managed window instead of the browser
+    #[serde(default)]
+    pub builtin: bool,
+}
+
+impl From<&WebAppDefinition> for WebApp {
+    fn from(def: &WebAppDefinition) -> Self {
+        WebApp {
+            id: def.id.to_string(),
+            name: def.name.to_string(),
+            url: def.url.to_string(),
+            icon: Some(def.icon.to_string()),
+            browser: None,
+            profile: None,
+            open_in_window: false,
+            builtin: true,
+        }
+    }
+}
+
+// Built-in web apps followed by user bookmarks; a bookmark with a built-in id overrides it
+pub fn merge_web_apps(bookmarks: &[WebApp]) -> Vec<WebApp> {
+    let mut web_apps: Vec<WebApp> = WEB_APPS.iter().map(WebApp::from).collect();
+
+    for bookmark in bookmarks {
+        match web_apps.iter_mut().find(|app| app.id == bookmark.id) {
+            Some(existing) => {
+                *existing = WebApp {
+                    builtin: existing.builtin,
+                    ..bookmark.clone()
+                }
+            }
+            None => web_apps.push(WebApp {
+                builtin: false,
+                ..bookmark.clone()
+            }),
+        }
+    }
+
+    web_apps
+}
+
+pub fn web_app_id_from_name(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::tool;
+
+    static WITH_PROFILES: ToolDefinition = ToolDefinition {
+        profiles: &[
+            LaunchProfileDefinition {
+                name: "Default",
+                args: &["--watch", "{home}/Media"],
+                env: &[],
+                working_dir: None,
+            },
+            LaunchProfileDefinition {
+                name: "Verbose",
+                args: &["--verbose"],
+                env: &[("SYNC_LOG_LEVEL", "debug")],
+                working_dir: Some("{tool_dir}"),
+            },
+        ],
+        ..tool("sync")
+    };
+
+    fn custom(name: &str, args: &[&str]) -> LaunchProfile {
+        LaunchProfile {
+            name: name.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            env: HashMap::new(),
+            working_dir: None,
+            builtin: false,
+        }
+    }
+
+    fn names(profiles: &[LaunchProfile]) -> Vec<&str> {
+        profiles.iter().map(|p| p.name.as_str()).collect()
+    }
+
+    #[test]
+    fn builtin_profiles_come_first() {
+        let profiles = merge_launch_profiles(&WITH_PROFILES, &[custom("Project X", &[])]);
+        assert_eq!(names(&profiles), ["Default", "Verbose", "Project X"]);
+        assert!(profiles[0].builtin && profiles[1].builtin);
+        assert!(!profiles[2].builtin);
+        assert_eq!(profiles[1].env["SYNC_LOG_LEVEL"], "debug");
+        assert_eq!(profiles[1].working_dir.as_deref(), Some("{tool_dir}"));
+    }
+
+    #[test]
+    fn user_profile_overrides_builtin_with_same_name() {
+        let profiles = merge_launch_profiles(
+            &WITH_PROFILES,
+            &[custom("Verbose", &["--verbose", "--trace"])],
+        );
+        assert_eq!(names(&profiles), ["Default", "Verbose"]);
+        assert_eq!(profiles[1].args, ["--verbose", "--trace"]);
+        assert!(profiles[1].env.is_empty());
+        assert_eq!(profiles[1].working_dir, None);
+        // Still listed as built-in, so removing the override restores it
+        assert!(profiles[1].builtin);
+    }
+
+    #[test]
+    fn saved_profiles_cannot_claim_to_be_builtin() {
+        let profile = LaunchProfile {
+            builtin: true,
+            ..custom("Project X", &[])
+        };
+        let profiles = merge_launch_profiles(&tool("plain"), &[profile]);
+        assert_eq!(names(&profiles), ["Project X"]);
+        assert!(!profiles[0].builtin);
+    }
+}
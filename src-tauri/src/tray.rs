@@ -0,0 +1,319 @@
+use std::collections::HashMap;
+use std::fs;
+
+use serde::Serialize;
+use tauri::{
+    menu::{Menu, MenuItem, PredefinedMenuItem, Submenu},
+    AppHandle, Emitter, Manager, Runtime,
+};
+
+use crate::process::request_update;
+use crate::profiles::{launch_profile, launch_profiles};
+use crate::scheduler::{self, SchedulerMessage};
+use crate::supervisor::{is_supervised, quit_tool};
+use crate::tools::{find_tool, ArtifactKind, ToolDefinition, TOOLS};
+use crate::{
+    get_installed_tools, get_installed_version, get_tool_logs_dir, get_web_apps, launch_tool,
+    open_web_app_by_id, tool_app_path, AppState,
+};
+
+pub const TRAY_ID: &str = "main-tray";
+
+// Sent to the window to confirm an uninstall started from the tray
+#[derive(Debug, Serialize, Clone)]
+pub struct UninstallRequest {
+    pub tool_id: String,
+    pub tool_name: String,
+}
+
+pub fn create_tray_menu<R: Runtime>(
+    app: &AppHandle<R>,
+    installed_tools: &[String],
+    available_updates: &HashMap<String, String>,
+) -> tauri::Result<Menu<R>> {
+    let mut items: Vec<Box<dyn tauri::menu::IsMenuItem<R>>> = Vec::new();
+
+    // Add a submenu per installed tool, flagging the ones with a pending update
+    for tool in TOOLS {
+        if !installed_tools.iter().any(|id| id == tool.id) {
+            continue;
+        }
+
+        let latest_version = available_updates.get(tool.id);
+        let running = is_supervised(app, tool.id);
+        let mut label = match latest_version {
+            Some(version) => format!("{} — update available {}", tool.name, version),
+            None => tool.name.to_string(),
+        };
+        if running {
+            label = format!("● {}", label);
+        }
+        let submenu = create_tool_submenu(app, tool, latest_version, running)?;
+        items.push(Box::new(Submenu::with_id_and_items(
+            app,
+            format!("tool:{}", tool.id),
+            label,
+            true,
+            &submenu.iter().map(|b| b.as_ref()).collect::<Vec<_>>(),
+        )?));
+    }
+
+    // Always show web apps
+    for web_app in get_web_apps() {
+        items.push(Box::new(MenuItem::with_id(
+            app,
+            format!("web:{}", web_app.id),
+            web_app.name,
+            true,
+            None::<&str>,
+        )?));
+    }
+
+    items.push(Box::new(PredefinedMenuItem::separator(app)?));
+    if !available_updates.is_empty() {
+        items.push(Box::new(MenuItem::with_id(
+            app,
+            "update-all",
+            "Update All",
+            true,
+            None::<&str>,
+        )?));
+    }
+    items.push(Box::new(MenuItem::with_id(
+        app,
+        "check-updates",
+        "Check for Updates",
+        true,
+        None::<&str>,
+    )?));
+    items.push(Box::new(MenuItem::with_id(
+        app,
+        "open-launcher",
+        "Open Story Launcher",
+        true,
+        None::<&str>,
+    )?));
+    items.push(Box::new(PredefinedMenuItem::separator(app)?));
+    items.push(Box::new(MenuItem::with_id(
+        app,
+        "quit",
+        "Quit Story Launcher",
+        true,
+        None::<&str>,
+    )?));
+
+    // Build menu from refs
+    let item_refs: Vec<&dyn tauri::menu::IsMenuItem<R>> =
+        items.iter().map(|b| b.as_ref()).collect();
+    Menu::with_items(app, &item_refs)
+}
+
+fn create_tool_submenu<R: Runtime>(
+    app: &AppHandle<R>,
+    tool: &ToolDefinition,
+    latest_version: Option<&String>,
+    running: bool,
+) -> tauri::Result<Vec<Box<dyn tauri::menu::IsMenuItem<R>>>> {
+    let mut items: Vec<Box<dyn tauri::menu::IsMenuItem<R>>> = Vec::new();
+
+    if running {
+        items.push(Box::new(MenuItem::with_id(
+            app,
+            tool_menu_id(tool.id, "quit"),
+            "Quit",
+            true,
+            None::<&str>,
+        )?));
+    } else if tool.kind == ArtifactKind::App {
+        items.push(Box::new(MenuItem::with_id(
+            app,
+            tool_menu_id(tool.id, "launch"),
+            "Launch",
+            true,
+            None::<&str>,
+        )?));
+        for profile in launch_profiles(tool.id) {
+            items.push(Box::new(MenuItem::with_id(
+                app,
+                tool_menu_id(tool.id, &format!("profile:{}", profile.name)),
+                format!("Launch — {}", profile.name),
+                true,
+                None::<&str>,
+            )?));
+        }
+    }
+    if let Some(version) = latest_version {
+        items.push(Box::new(MenuItem::with_id(
+            app,
+            tool_menu_id(tool.id, "update"),
+            format!("Update to {}", version),
+            true,
+            None::<&str>,
+        )?));
+    }
+
+    // Version info is display-only
+    let version_label = match get_installed_version(tool.id) {
+        Some(version) => format!("Version {}", version),
+        None => "Version unknown".to_string(),
+    };
+    items.push(Box::new(PredefinedMenuItem::separator(app)?));
+    items.push(Box::new(MenuItem::with_id(
+        app,
+        tool_menu_id(tool.id, "version"),
+        version_label,
+        false,
+        None::<&str>,
+    )?));
+    items.push(Box::new(MenuItem::with_id(
+        app,
+        tool_menu_id(tool.id, "reveal"),
+        "Reveal in File Manager",
+        true,
+        None::<&str>,
+    )?));
+    items.push(Box::new(MenuItem::with_id(
+        app,
+        tool_menu_id(tool.id, "logs"),
+        "View Logs",
+        true,
+        None::<&str>,
+    )?));
+    items.push(Box::new(PredefinedMenuItem::separator(app)?));
+    items.push(Box::new(MenuItem::with_id(
+        app,
+        tool_menu_id(tool.id, "uninstall"),
+        "Uninstall",
+        true,
+        None::<&str>,
+    )?));
+
+    Ok(items)
+}
+
+// Per-tool tray ids look like `tool:<tool_id>:<action>`, with launch profiles
+// as `tool:<tool_id>:profile:<name>`
+fn tool_menu_id(tool_id: &str, action: &str) -> String {
+    format!("tool:{}:{}", tool_id, action)
+}
+
+fn parse_tool_menu_id(id: &str) -> Option<(&'static ToolDefinition, &str)> {
+    let (tool_id, action) = id.strip_prefix("tool:")?.split_once(':')?;
+    find_tool(tool_id).map(|tool| (tool, action))
+}
+
+// Rebuild the tray menu from the current install state and known updates
+pub fn refresh_tray<R: Runtime>(app: &AppHandle<R>) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+
+    let installed_tools = get_installed_tools();
+    let available_updates = app
+        .state::<AppState>()
+        .available_updates
+        .lock()
+        .unwrap()
+        .clone();
+
+    if let Ok(menu) = create_tray_menu(app, &installed_tools, &available_updates) {
+        let _ = tray.set_menu(Some(menu));
+    }
+}
+
+pub fn show_main_window<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+pub fn handle_menu_event<R: Runtime>(app: &AppHandle<R>, id: &str) {
+    match id {
+        "update-all" => {
+            let app = app.clone();
+            std::thread::spawn(move || update_all_tools(&app));
+        }
+        "check-updates" => {
+            // Runs in the background; the window refreshes on `updates-checked`
+            scheduler::send(app, SchedulerMessage::CheckNow);
+        }
+        "open-launcher" => show_main_window(app),
+        "quit" => {
+            app.exit(0);
+        }
+        id => {
+            if let Some(web_app_id) = id.strip_prefix("web:") {
+                let _ = open_web_app_by_id(app, web_app_id);
+            } else if let Some((tool, action)) = parse_tool_menu_id(id) {
+                handle_tool_action(app, tool, action);
+            }
+        }
+    }
+}
+
+fn handle_tool_action<R: Runtime>(app: &AppHandle<R>, tool: &ToolDefinition, action: &str) {
+    match action {
+        "launch" => {
+            let _ = launch_tool(app.clone(), tool.id.to_string());
+        }
+        "quit" => {
+            let _ = quit_tool(app.clone(), tool.id.to_string());
+        }
+        "update" => {
+            let app = app.clone();
+            let tool_id = tool.id.to_string();
+            std::thread::spawn(move || {
+                let _ = request_update(&app, &tool_id);
+                let _ = app.emit("tools-changed", ());
+            });
+        }
+        "reveal" => {
+            if let Some(path) = tool_app_path(tool.id) {
+                let _ = tauri_plugin_opener::reveal_item_in_dir(path);
+            }
+        }
+        "logs" => {
+            let logs_dir = get_tool_logs_dir(tool.id);
+            if fs::create_dir_all(&logs_dir).is_ok() {
+                let _ = tauri_plugin_opener::open_path(logs_dir, None::<&str>);
+            }
+        }
+        // Removing a tool is hard to undo, so the window asks first
+        "uninstall" => {
+            show_main_window(app);
+            let _ = app.emit(
+                "uninstall-request",
+                UninstallRequest {
+                    tool_id: tool.id.to_string(),
+                    tool_name: tool.name.to_string(),
+                },
+            );
+        }
+        action => {
+            if let Some(profile) = action.strip_prefix("profile:") {
+                let _ = launch_profile(app, tool.id, profile);
+            }
+        }
+    }
+}
+
+fn update_all_tools<R: Runtime>(app: &AppHandle<R>) {
+    let tool_ids: Vec<String> = app
+        .state::<AppState>()
+        .available_updates
+        .lock()
+        .unwrap()
+        .keys()
+        .cloned()
+        .collect();
+
+    // Running tools update once they quit; the rest install right away and
+    // clear their pending update
+    for tool_id in tool_ids {
+        let _ = request_update(app, &tool_id);
+    }
+
+    // Let the window re-query status since the updates happened behind its back
+    let _ = app.emit("tools-changed", ());
+}
@@ -0,0 +1,111 @@
+// Dedicated launcher-managed windows for web apps
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+
+use crate::error::LauncherError;
+use crate::tools::WebApp;
+use crate::{get_tools_dir, load_config, update_config};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct WindowGeometry {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+fn window_label(web_app_id: &str) -> String {
+    format!("webapp-{}", web_app_id)
+}
+
+pub fn open_web_app_window<R: Runtime>(
+    app: &AppHandle<R>,
+    web_app: &WebApp,
+) -> Result<(), LauncherError> {
+    let label = window_label(&web_app.id);
+
+    // Focus the existing window instead of opening a second one
+    if let Some(window) = app.get_webview_window(&label) {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+        return Ok(());
+    }
+
+    let url = web_app
+        .url
+        .parse()
+        .map_err(|e| LauncherError::InvalidInput(format!("Invalid web app URL: {}", e)))?;
+
+    // Each web app gets its own data directory so sessions stay isolated
+    let data_dir = get_tools_dir().join("webviews").join(&web_app.id);
+
+    let mut builder = WebviewWindowBuilder::new(app, &label, WebviewUrl::External(url))
+        .title(&web_app.name)
+        .data_directory(data_dir)
+        .data_store_identifier(data_store_identifier(&web_app.id));
+
+    builder = match load_config().window_geometry.get(&web_app.id) {
+        Some(geometry) => builder
+            .inner_size(geometry.width, geometry.height)
+            .position(geometry.x, geometry.y),
+        None => builder.inner_size(1200.0, 800.0).center(),
+    };
+
+    let window = builder
+        .build()
+        .map_err(|e| LauncherError::Launch(format!("Failed to open window: {}", e)))?;
+
+    // Persist size and position when the window is closed or loses focus
+    let window_clone = window.clone();
+    let web_app_id = web_app.id.clone();
+    window.on_window_event(move |event| match event {
+        tauri::WindowEvent::CloseRequested { .. } | tauri::WindowEvent::Focused(false) => {
+            save_window_geometry(&window_clone, &web_app_id);
+        }
+        _ => {}
+    });
+
+    Ok(())
+}
+
+fn save_window_geometry<R: Runtime>(window: &WebviewWindow<R>, web_app_id: &str) {
+    let (Ok(scale), Ok(position), Ok(size)) = (
+        window.scale_factor(),
+        window.outer_position(),
+        window.inner_size(),
+    ) else {
+        return;
+    };
+
+    let position = position.to_logical::<f64>(scale);
+    let size = size.to_logical::<f64>(scale);
+    let geometry = WindowGeometry {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+    };
+
+    let _ = update_config(|config| {
+        config
+            .window_geometry
+            .insert(web_app_id.to_string(), geometry)
+    });
+}
+
+// WKWebView has no data directory, so macOS keys its data store by a stable
+// 16-byte identifier instead (FNV-1a of the web app id, twice with different seeds)
+fn data_store_identifier(web_app_id: &str) -> [u8; 16] {
+    let hash = |seed: u64| {
+        web_app_id.bytes().fold(seed, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        })
+    };
+
+    let mut identifier = [0u8; 16];
+    identifier[..8].copy_from_slice(&hash(0xcbf29ce484222325).to_be_bytes());
+    identifier[8..].copy_from_slice(&hash(0x84222325cbf29ce4).to_be_bytes());
+    identifier
+}
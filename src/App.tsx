@@ -11,17 +11,106 @@ import StoryLogo from "./assets/StoryLogo.svg";
 const APP_VERSION = "0.2.0";
 const STORE_NAME = "settings.json";
 
+// Mirrors LauncherError in src-tauri/src/error.rs
+interface LauncherError {
+  code: string;
+  message: string;
+  reset?: number | null;
+  tool_id?: string;
+}
+
 interface ToolStatus {
   installed: boolean;
   installed_version: string | null;
+  recorded_version: string | null;
+  version_mismatch: boolean;
+  source: "managed" | "external" | null;
+  discovered_path: string | null;
   latest_version: string | null;
   has_update: boolean;
-  error: string | null;
+  running: boolean;
+  update_queued: boolean;
+  error: LauncherError | null;
 }
 
 interface ActionResult {
   success: boolean;
   message: string;
+  error: LauncherError | null;
+}
+
+type UpdateMode = "manual" | "notify" | "auto_when_not_running" | "auto_scheduled";
+
+interface UpdatePolicy {
+  mode: UpdateMode;
+  maintenance_window: { start: string; end: string };
+}
+
+interface UpdatePolicySettings {
+  default: UpdatePolicy;
+  tools: Record<string, UpdatePolicy>;
+  deferrals: Record<string, number>;
+}
+
+interface InstallStep {
+  tool_id: string;
+  version: string;
+  installed_version: string | null;
+  required_by: string;
+}
+
+interface InstallPlan {
+  steps: InstallStep[];
+  error: LauncherError | null;
+}
+
+interface InstallResult extends ActionResult {
+  dependencies: InstallStep[]; // installed before the tool, in order
+}
+
+// The result's message plus whatever dependencies came along with it
+function installMessage(text: string, result: InstallResult): string {
+  if (result.dependencies.length === 0) return text;
+  const installed = result.dependencies.map((step) => `${step.tool_id} ${step.version}`).join(", ");
+  return `${text} Also installed ${installed}.`;
+}
+
+interface LaunchProfile {
+  name: string;
+  args: string[];
+  env: Record<string, string>;
+  working_dir: string | null;
+  builtin: boolean;
+}
+
+interface ToolLogLine {
+  tool_id: string;
+  line: string;
+}
+
+interface ToolExit {
+  tool_id: string;
+  code: number | null;
+  crashed: boolean;
+  restarting: boolean;
+}
+
+interface UninstallRequest {
+  tool_id: string;
+  tool_name: string;
+}
+
+interface PendingInstall {
+  tool_id: string;
+  tool_name: string;
+  version: string | null;
+}
+
+interface ConfigRecovery {
+  error: LauncherError;
+  backup_path: string | null;
+  recovered_tools: Record<string, string>;
+  recovered_at: number;
 }
 
 interface WebApp {
@@ -35,13 +124,29 @@ interface WebApp {
 interface Settings {
   autoUpdateOnLaunch: boolean;
   launchAtLogin: boolean;
+  autoRestart: boolean;
+  startWithLauncher: boolean;
 }
 
 const defaultSettings: Settings = {
   autoUpdateOnLaunch: true,
   launchAtLogin: false,
+  autoRestart: false,
+  startWithLauncher: false,
 };
 
+interface StartupSettings {
+  enabled: boolean;
+  delay_secs: number;
+  after: string[];
+  profile: string | null;
+}
+
+interface ToolSettings {
+  auto_restart: boolean;
+  startup: StartupSettings;
+}
+
 interface AppUpdate {
   version: string;
   downloadedAndReady: boolean;
@@ -194,6 +299,92 @@ function AppUpdateBanner({
   return null;
 }
 
+// Named launch profiles from the tool definition and config.json
+function ToolProfiles({
+  toolId,
+  onMessage
+}: {
+  toolId: string;
+  onMessage: (msg: { type: 'success' | 'error'; text: string } | null) => void;
+}) {
+  const [profiles, setProfiles] = useState<LaunchProfile[]>([]);
+
+  useEffect(() => {
+    invoke<LaunchProfile[]>("get_launch_profiles", { toolId })
+      .then(setProfiles)
+      .catch(console.error);
+  }, [toolId]);
+
+  const handleLaunch = async (profile: string) => {
+    try {
+      const result = await invoke<ActionResult>("launch_tool_profile", { toolId, profile });
+      if (!result.success) {
+        onMessage({ type: 'error', text: result.message });
+      }
+    } catch (err) {
+      onMessage({ type: 'error', text: String(err) });
+    }
+  };
+
+  if (profiles.length === 0) return null;
+
+  return (
+    <div className="mt-2 flex flex-wrap gap-2">
+      {profiles.map((profile) => (
+        <button
+          key={profile.name}
+          onClick={() => handleLaunch(profile.name)}
+          title={profile.args.join(" ")}
+          className="px-3 py-1.5 bg-zinc-800 hover:bg-zinc-700 text-zinc-300 text-xs font-medium rounded-lg transition-colors"
+        >
+          Open: {profile.name}
+        </button>
+      ))}
+    </div>
+  );
+}
+
+const MAX_OUTPUT_LINES = 500;
+
+// Recent output of a tool started from the launcher, followed live while open
+function ToolOutput({ toolId }: { toolId: string }) {
+  const [open, setOpen] = useState(false);
+  const [lines, setLines] = useState<string[]>([]);
+
+  useEffect(() => {
+    if (!open) return;
+
+    invoke<string[]>("tail_tool_log", { toolId, lines: 200 })
+      .then(setLines)
+      .catch(console.error);
+    const unlisten = listen<ToolLogLine>("tool-log", (event) => {
+      if (event.payload.tool_id !== toolId) return;
+      const line = event.payload.line;
+      setLines((prev) => [...prev, line].slice(-MAX_OUTPUT_LINES));
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [open, toolId]);
+
+  return (
+    <div className="mt-2">
+      <button
+        onClick={() => setOpen(!open)}
+        className="text-xs text-zinc-500 hover:text-zinc-300 transition-colors"
+      >
+        {open ? "Hide output" : "Show output"}
+      </button>
+      {open && (
+        <pre className="mt-2 p-3 max-h-64 overflow-auto bg-zinc-950 border border-zinc-800 rounded-lg text-xs text-zinc-400 whitespace-pre-wrap">
+          {lines.length > 0 ? lines.join("\n") : "No output yet"}
+        </pre>
+      )}
+    </div>
+  );
+}
+
 function ToolCard({
   status,
   isLoading,
@@ -201,7 +392,9 @@ function ToolCard({
   onRefresh,
   onInstall,
   onUpdate,
-  onLaunch
+  onLaunch,
+  onQuit,
+  onAdopt
 }: {
   status: ToolStatus | null;
   isLoading: boolean;
@@ -210,6 +403,8 @@ function ToolCard({
   onInstall: () => void;
   onUpdate: () => void;
   onLaunch: () => void;
+  onQuit: () => void;
+  onAdopt: () => void;
 }) {
   return (
     <div className="bg-zinc-900 rounded-xl border border-zinc-800 overflow-hidden">
@@ -241,7 +436,7 @@ function ToolCard({
                   <svg className="w-4 h-4" fill="none" stroke="currentColor" viewBox="0 0 24 24">
                     <path strokeLinecap="round" strokeLinejoin="round" strokeWidth={2} d="M12 9v2m0 4h.01m-6.938 4h13.856c1.54 0 2.502-1.667 1.732-3L13.732 4c-.77-1.333-2.694-1.333-3.464 0L3.34 16c-.77 1.333.192 3 1.732 3z" />
                   </svg>
-                  <span className="text-sm">{status.error}</span>
+                  <span className="text-sm">{status.error.message}</span>
                 </div>
               ) : (
                 <>
@@ -250,6 +445,24 @@ function ToolCard({
                     <span className="text-sm text-zinc-300">
                       {status.installed ? `v${status.installed_version}` : "Not installed"}
                     </span>
+                    {status.running && (
+                      <span className="px-1.5 py-0.5 bg-green-500/20 text-green-400 text-xs rounded font-medium">
+                        Running
+                      </span>
+                    )}
+                    {status.source === "external" && (
+                      <span className="px-1.5 py-0.5 bg-zinc-700/50 text-zinc-400 text-xs rounded font-medium">
+                        External
+                      </span>
+                    )}
+                    {status.version_mismatch && (
+                      <span
+                        className="px-1.5 py-0.5 bg-amber-500/20 text-amber-400 text-xs rounded font-medium"
+                        title={`Launcher records ${status.recorded_version ? `v${status.recorded_version}` : "no install"}`}
+                      >
+                        Changed outside launcher
+                      </span>
+                    )}
                   </div>
                   <div className="flex items-center gap-2">
                     <span className="text-xs text-zinc-500 w-20">Latest:</span>
@@ -258,7 +471,7 @@ function ToolCard({
                     </span>
                     {status.has_update && (
                       <span className="px-1.5 py-0.5 bg-amber-500/20 text-amber-400 text-xs rounded font-medium">
-                        Update available
+                        {status.update_queued ? "Updates when closed" : "Update available"}
                       </span>
                     )}
                   </div>
@@ -297,7 +510,15 @@ function ToolCard({
             >
               Open
             </button>
-            {status.has_update && (
+            {status.running && (
+              <button
+                onClick={onQuit}
+                className="px-4 py-2 bg-zinc-800 hover:bg-zinc-700 text-white text-sm font-medium rounded-lg transition-colors"
+              >
+                Quit
+              </button>
+            )}
+            {status.has_update && !status.update_queued && (
               <button
                 onClick={onUpdate}
                 className="px-4 py-2 bg-zinc-800 hover:bg-zinc-700 text-white text-sm font-medium rounded-lg transition-colors"
@@ -306,6 +527,24 @@ function ToolCard({
               </button>
             )}
           </>
+        ) : status?.discovered_path ? (
+          <>
+            <button
+              onClick={onAdopt}
+              title={status.discovered_path}
+              className="px-4 py-2 bg-blue-600 hover:bg-blue-500 text-white text-sm font-medium rounded-lg transition-colors"
+            >
+              Use Existing Install
+            </button>
+            {status.latest_version && (
+              <button
+                onClick={onInstall}
+                className="px-4 py-2 bg-zinc-800 hover:bg-zinc-700 text-white text-sm font-medium rounded-lg transition-colors"
+              >
+                Install Fresh Copy
+              </button>
+            )}
+          </>
         ) : status?.latest_version ? (
           <button
             onClick={onInstall}
@@ -394,6 +633,8 @@ function AppsPage({
   onInstall,
   onUpdate,
   onLaunch,
+  onQuit,
+  onAdopt,
   onMessage
 }: {
   status: ToolStatus | null;
@@ -404,6 +645,8 @@ function AppsPage({
   onInstall: () => void;
   onUpdate: () => void;
   onLaunch: () => void;
+  onQuit: () => void;
+  onAdopt: () => void;
   onMessage: (msg: { type: 'success' | 'error'; text: string } | null) => void;
 }) {
   const handleOpenWebApp = async (url: string) => {
@@ -442,7 +685,11 @@ function AppsPage({
               onInstall={onInstall}
               onUpdate={onUpdate}
               onLaunch={onLaunch}
+              onQuit={onQuit}
+              onAdopt={onAdopt}
             />
+            {status?.installed && <ToolProfiles toolId="resolve-sync" onMessage={onMessage} />}
+            {status?.installed && <ToolOutput toolId="resolve-sync" />}
           </div>
 
           <div>
@@ -481,8 +728,8 @@ function SettingsPage({
             <div className="space-y-4">
               <div className="flex items-center justify-between">
                 <div>
-                  <div className="text-sm font-medium text-white">Auto-update tools on launch</div>
-                  <div className="text-xs text-zinc-500">Automatically update installed tools when updates are available</div>
+                  <div className="text-sm font-medium text-white">Auto-update tools</div>
+                  <div className="text-xs text-zinc-500">Install updates in the background whenever a tool isn't running</div>
                 </div>
                 <Toggle
                   enabled={settings.autoUpdateOnLaunch}
@@ -500,6 +747,28 @@ function SettingsPage({
                   onChange={(value) => onSettingsChange('launchAtLogin', value)}
                 />
               </div>
+
+              <div className="flex items-center justify-between">
+                <div>
+                  <div className="text-sm font-medium text-white">Restart Resolve Sync if it crashes</div>
+                  <div className="text-xs text-zinc-500">Keep syncing running when it was started from the launcher</div>
+                </div>
+                <Toggle
+                  enabled={settings.autoRestart}
+                  onChange={(value) => onSettingsChange('autoRestart', value)}
+                />
+              </div>
+
+              <div className="flex items-center justify-between">
+                <div>
+                  <div className="text-sm font-medium text-white">Start Resolve Sync with the launcher</div>
+                  <div className="text-xs text-zinc-500">Open it automatically whenever Story Launcher starts</div>
+                </div>
+                <Toggle
+                  enabled={settings.startWithLauncher}
+                  onChange={(value) => onSettingsChange('startWithLauncher', value)}
+                />
+              </div>
             </div>
           </div>
 
@@ -545,12 +814,27 @@ function App() {
     const loadSettings = async () => {
       try {
         const store = await load(STORE_NAME);
-        const autoUpdate = await store.get<boolean>('autoUpdateOnLaunch');
         const launchAtLogin = await store.get<boolean>('launchAtLogin');
+        // Auto-update used to live in the store; carry an opt-out over to the policy once
+        if (!(await store.get<boolean>('autoUpdatePolicyMigrated'))) {
+          if ((await store.get<boolean>('autoUpdateOnLaunch')) === false) {
+            await invoke<ActionResult>("set_update_policy", {
+              toolId: null,
+              policy: { mode: "notify" },
+            });
+          }
+          await store.set('autoUpdatePolicyMigrated', true);
+          await store.save();
+        }
+        // Auto-update is enforced by the backend, so its policy is the source of truth
+        const policies = await invoke<UpdatePolicySettings>("get_update_policies");
+        const toolSettings = await invoke<ToolSettings>("get_tool_settings", { toolId: "resolve-sync" });
 
         setSettings({
-          autoUpdateOnLaunch: autoUpdate ?? defaultSettings.autoUpdateOnLaunch,
+          autoUpdateOnLaunch: policies.default.mode !== "manual" && policies.default.mode !== "notify",
           launchAtLogin: launchAtLogin ?? defaultSettings.launchAtLogin,
+          autoRestart: toolSettings.auto_restart,
+          startWithLauncher: toolSettings.startup.enabled,
         });
 
         // Sync autostart state with stored setting
@@ -580,6 +864,26 @@ function App() {
       await store.set(key, value);
       await store.save();
 
+      if (key === 'startWithLauncher') {
+        // Keep any delay, order or profile set in config.json
+        const toolSettings = await invoke<ToolSettings>("get_tool_settings", { toolId: "resolve-sync" });
+        await invoke<ActionResult>("set_startup_settings", {
+          toolId: "resolve-sync",
+          settings: { ...toolSettings.startup, enabled: value },
+        });
+      }
+
+      if (key === 'autoRestart') {
+        await invoke<ActionResult>("set_auto_restart", { toolId: "resolve-sync", enabled: value });
+      }
+
+      if (key === 'autoUpdateOnLaunch') {
+        await invoke<ActionResult>("set_update_policy", {
+          toolId: null,
+          policy: { mode: value ? "auto_when_not_running" : "notify" },
+        });
+      }
+
       // Handle launch at login toggle
       if (key === 'launchAtLogin') {
         if (value) {
@@ -604,9 +908,15 @@ function App() {
       setStatus({
         installed: false,
         installed_version: null,
+        recorded_version: null,
+        version_mismatch: false,
+        source: null,
+        discovered_path: null,
         latest_version: null,
         has_update: false,
-        error: String(err),
+        running: false,
+        update_queued: false,
+        error: { code: "internal", message: String(err) },
       });
       return null;
     } finally {
@@ -615,12 +925,24 @@ function App() {
   }, []);
 
   const handleInstall = useCallback(async () => {
-    setIsInstalling(true);
     setMessage(null);
+
+    // Dependencies are installed first, so show what else is coming along
+    const plan = await invoke<InstallPlan>("get_install_plan", { toolId: "resolve-sync" });
+    if (plan.error) {
+      setMessage({ type: 'error', text: plan.error.message });
+      return;
+    }
+    if (plan.steps.length > 0) {
+      const steps = plan.steps.map((step) => `${step.tool_id} ${step.version}`).join(", ");
+      if (!window.confirm(`Resolve Sync needs ${steps}. Install them too?`)) return;
+    }
+
+    setIsInstalling(true);
     try {
-      const result = await invoke<ActionResult>("install_tool", { toolId: "resolve-sync" });
+      const result = await invoke<InstallResult>("install_tool", { toolId: "resolve-sync" });
       if (result.success) {
-        setMessage({ type: 'success', text: result.message });
+        setMessage({ type: 'success', text: installMessage(result.message, result) });
         await checkStatus();
       } else {
         setMessage({ type: 'error', text: result.message });
@@ -636,9 +958,26 @@ function App() {
     setIsInstalling(true);
     setMessage(null);
     try {
-      const result = await invoke<ActionResult>("update_tool", { toolId: "resolve-sync" });
+      // Adopted copies are only updated once they've been moved into the launcher
+      if (status?.source === "external") {
+        if (!window.confirm("Resolve Sync is installed outside the launcher. Move it into the launcher to update it?")) return;
+        const moved = await invoke<ActionResult>("adopt_tool", { toolId: "resolve-sync", path: null, migrate: true });
+        if (!moved.success) {
+          setMessage({ type: 'error', text: moved.message });
+          return;
+        }
+      }
+
+      // Replacing a running tool would break it, so either quit it first or wait for it to exit
+      let command = "update_tool";
+      if (status?.running) {
+        command = window.confirm("Resolve Sync is running. Quit and reopen it to update now?\n\nChoose Cancel to update when it's next closed.")
+          ? "restart_and_update"
+          : "queue_update";
+      }
+      const result = await invoke<InstallResult>(command, { toolId: "resolve-sync" });
       if (result.success) {
-        setMessage({ type: 'success', text: 'Update complete!' });
+        setMessage({ type: 'success', text: command === "queue_update" ? result.message : installMessage('Update complete!', result) });
         await checkStatus();
       } else {
         setMessage({ type: 'error', text: result.message });
@@ -648,7 +987,7 @@ function App() {
     } finally {
       setIsInstalling(false);
     }
-  }, [checkStatus]);
+  }, [checkStatus, status?.running, status?.source]);
 
   const handleLaunch = async () => {
     try {
@@ -656,35 +995,44 @@ function App() {
       if (!result.success) {
         setMessage({ type: 'error', text: result.message });
       }
+      await checkStatus();
     } catch (err) {
       setMessage({ type: 'error', text: String(err) });
     }
   };
 
-  // Auto-check and auto-update on app launch
-  useEffect(() => {
-    if (!settingsLoaded) return;
-
-    const initializeApp = async () => {
-      const toolStatus = await checkStatus();
-
-      // If auto-update is enabled and there's an update available, run it silently
-      if (settings.autoUpdateOnLaunch && toolStatus?.has_update) {
-        setIsInstalling(true);
-        try {
-          await invoke<ActionResult>("update_tool", { toolId: "resolve-sync" });
-          // Refresh status after update
-          await checkStatus();
-        } catch (err) {
-          console.error("Auto-update failed:", err);
-        } finally {
-          setIsInstalling(false);
-        }
+  const handleQuit = async () => {
+    try {
+      const result = await invoke<ActionResult>("quit_tool", { toolId: "resolve-sync" });
+      if (!result.success) {
+        setMessage({ type: 'error', text: result.message });
       }
-    };
+    } catch (err) {
+      setMessage({ type: 'error', text: String(err) });
+    }
+  };
 
-    initializeApp();
-  }, [settingsLoaded, settings.autoUpdateOnLaunch, checkStatus]);
+  // Register a copy found in /Applications in place rather than reinstalling
+  const handleAdopt = useCallback(async () => {
+    setMessage(null);
+    try {
+      const result = await invoke<ActionResult>("adopt_tool", {
+        toolId: "resolve-sync",
+        path: null,
+        migrate: false,
+      });
+      setMessage({ type: result.success ? 'success' : 'error', text: result.message });
+      await checkStatus();
+    } catch (err) {
+      setMessage({ type: 'error', text: String(err) });
+    }
+  }, [checkStatus]);
+
+  // Check status on app launch; updates themselves are applied by the backend policy
+  useEffect(() => {
+    if (!settingsLoaded) return;
+    checkStatus();
+  }, [settingsLoaded, checkStatus]);
 
   // Update tray icon when status changes
   useEffect(() => {
@@ -692,9 +1040,9 @@ function App() {
     invoke("set_tray_update_icon", { hasUpdate }).catch(console.error);
   }, [status?.has_update]);
 
-  // Listen for tray menu "Check for Updates" event
+  // Refresh after a background or tray-triggered update check
   useEffect(() => {
-    const unlisten = listen("check-updates", () => {
+    const unlisten = listen("updates-checked", () => {
       checkStatus();
     });
 
@@ -703,6 +1051,96 @@ function App() {
     };
   }, [checkStatus]);
 
+  // Refresh after the tray installed or updated tools in the background
+  useEffect(() => {
+    const unlisten = listen("tools-changed", () => {
+      checkStatus();
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [checkStatus]);
+
+  // Report crashes of tools the launcher started
+  useEffect(() => {
+    const unlisten = listen<ToolExit>("tool-exited", (event) => {
+      const exit = event.payload;
+      if (exit.crashed) {
+        const code = exit.code === null ? "" : ` (exit code ${exit.code})`;
+        const restarting = exit.restarting ? ", restarting" : "";
+        setMessage({ type: 'error', text: `Resolve Sync quit unexpectedly${code}${restarting}` });
+      }
+      checkStatus();
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [checkStatus]);
+
+  // Confirm installs requested through story-launcher:// links
+  useEffect(() => {
+    const confirmInstall = async (request: PendingInstall) => {
+      const version = request.version ? ` ${request.version}` : "";
+      const accept = window.confirm(`Install ${request.tool_name}${version}?`);
+      setIsInstalling(accept);
+      try {
+        const result = await invoke<InstallResult>("confirm_deep_link_install", { accept });
+        if (accept) {
+          setMessage({ type: result.success ? 'success' : 'error', text: installMessage(result.message, result) });
+        }
+      } catch (err) {
+        setMessage({ type: 'error', text: String(err) });
+      } finally {
+        setIsInstalling(false);
+      }
+    };
+
+    invoke<PendingInstall | null>("get_pending_deep_link_install").then((request) => {
+      if (request) confirmInstall(request);
+    });
+    const unlisten = listen<PendingInstall>("deep-link-install-request", (event) => {
+      confirmInstall(event.payload);
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  // Confirm uninstalls started from the tray
+  useEffect(() => {
+    const unlisten = listen<UninstallRequest>("uninstall-request", async (event) => {
+      const request = event.payload;
+      if (!window.confirm(`Uninstall ${request.tool_name}?`)) return;
+      try {
+        const result = await invoke<ActionResult>("uninstall_tool", { toolId: request.tool_id });
+        setMessage({ type: result.success ? 'success' : 'error', text: result.message });
+      } catch (err) {
+        setMessage({ type: 'error', text: String(err) });
+      }
+      checkStatus();
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [checkStatus]);
+
+  // Tell the user when a corrupt config.json had to be rebuilt
+  useEffect(() => {
+    invoke<ConfigRecovery | null>("get_config_recovery").then((recovery) => {
+      if (!recovery) return;
+      const backup = recovery.backup_path ? ` The old file was saved to ${recovery.backup_path}.` : "";
+      setMessage({
+        type: 'error',
+        text: `Your launcher settings were unreadable and have been rebuilt from installed apps.${backup}`,
+      });
+      invoke("dismiss_config_recovery").catch(console.error);
+    }).catch(console.error);
+  }, []);
+
   // Check for app updates on launch
   useEffect(() => {
     const checkForAppUpdate = async () => {
@@ -777,6 +1215,8 @@ function App() {
           onInstall={handleInstall}
           onUpdate={handleUpdate}
           onLaunch={handleLaunch}
+          onQuit={handleQuit}
+          onAdopt={handleAdopt}
           onMessage={setMessage}
         />
       ) : (